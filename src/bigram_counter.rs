@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use crate::word_counter::corpus_words;
+
+// Counts of adjacent word pairs, keyed by the first word.
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BigramCounter {
+    pairs: HashMap<String, HashMap<String, u32>>,
+}
+
+impl BigramCounter {
+    pub fn new() -> Self {
+        BigramCounter {
+            pairs: HashMap::new(),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Self {
+        let mut counter = Self::new();
+        let mut previous: Option<String> = None;
+        for word in corpus_words(input) {
+            if let Some(prev) = previous {
+                counter.add(&prev, &word);
+            }
+            previous = Some(word);
+        }
+        counter
+    }
+
+    pub fn add(&mut self, first: &str, second: &str) {
+        let count = self.pairs
+        .entry(first.trim().to_lowercase())
+        .or_default()
+        .entry(second.trim().to_lowercase())
+        .or_insert(0);
+        *count += 1;
+    }
+
+    pub fn get(&self, first: &str, second: &str) -> u32 {
+        self.pairs
+        .get(first)
+        .and_then(|following| following.get(second))
+        .copied()
+        .unwrap_or(0)
+    }
+
+    // Adds the counts of `other` to this counter.
+    pub fn merge(&mut self, other: &BigramCounter) {
+        for (first, following) in &other.pairs {
+            let counts = self.pairs.entry(first.clone()).or_default();
+            for (second, &count) in following {
+                *counts.entry(second.clone()).or_insert(0) += count;
+            }
+        }
+    }
+
+    // The number of pairs starting with `first`.
+    pub fn following_count(&self, first: &str) -> u32 {
+        self.pairs
+        .get(first)
+        .map_or(0, |following| following.values().sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bigrams_from_string() {
+        let counter = BigramCounter::from_str("the cat\nThe cat sat on the mat");
+
+        assert_eq!(counter.get("the", "cat"), 2);
+        assert_eq!(counter.get("cat", "the"), 1);
+        assert_eq!(counter.get("cat", "mat"), 0);
+        assert_eq!(counter.following_count("the"), 3);
+        assert_eq!(counter.following_count("mat"), 0);
+    }
+
+    #[test]
+    fn merge() {
+        let mut counter = BigramCounter::from_str("the cat the cat");
+        let other = BigramCounter::from_str("the cat the dog");
+
+        counter.merge(&other);
+
+        assert_eq!(counter.get("the", "cat"), 3);
+        assert_eq!(counter.get("the", "dog"), 1);
+        assert_eq!(counter.following_count("cat"), 2);
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use crate::distance::unrestricted_damerau_levenshtein as distance;
+
+// A BK-tree over words, where each child is keyed on its distance to its
+// parent. By the triangle inequality a query within `max_distance` only
+// descends into the children at most `max_distance` away from the distance
+// of the query to their parent, so lookups scale with the number of words
+// rather than with the number of possible edits. A transposition counts as
+// one edit, like in the edits of the spell checker.
+#[derive(Default, Clone)]
+pub struct BkTree {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone)]
+struct Node {
+    word: String,
+    children: HashMap<usize, usize>,
+}
+
+impl Node {
+    fn new(word: &str) -> Self {
+        Node {
+            word: word.to_owned(),
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl BkTree {
+    pub fn new() -> Self {
+        BkTree { nodes: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // Words already in the tree are not inserted again.
+    pub fn insert(&mut self, word: &str) {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::new(word));
+            return;
+        }
+        let mut current = 0;
+        loop {
+            let d = distance(&self.nodes[current].word, word);
+            if d == 0 {
+                return;
+            }
+            match self.nodes[current].children.get(&d) {
+                Some(&child) => current = child,
+                None => {
+                    let index = self.nodes.len();
+                    self.nodes[current].children.insert(d, index);
+                    self.nodes.push(Node::new(word));
+                    return;
+                }
+            }
+        }
+    }
+
+    // The words within `max_distance` of `word` paired with their distance,
+    // sorted by distance and then alphabetically.
+    pub fn query(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        let mut result = Vec::new();
+        let mut stack = if self.nodes.is_empty() { Vec::new() } else { vec![0] };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let d = distance(&node.word, word);
+            if d <= max_distance {
+                result.push((node.word.clone(), d));
+            }
+            stack.extend(
+                node.children
+                .iter()
+                .filter(|(&child_distance, _)| child_distance.abs_diff(d) <= max_distance)
+                .map(|(_, &child)| child)
+            );
+        }
+        result.sort_unstable_by(|(a, x), (b, y)| x.cmp(y).then_with(|| a.cmp(b)));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_tree() {
+        let tree = BkTree::new();
+
+        assert!(tree.is_empty());
+        assert!(tree.query("word", 2).is_empty());
+    }
+
+    #[test]
+    fn insert_skips_duplicates() {
+        let mut tree = BkTree::new();
+
+        for word in ["cat", "cot", "cat", "dog"].iter() {
+            tree.insert(word);
+        }
+
+        assert_eq!(tree.len(), 3);
+    }
+
+    #[test]
+    fn query_matches_a_linear_scan() {
+        let words = ["", "a", "ab", "ba", "abc", "acb", "ca", "cat", "act", "kitten", "sitting", "mitten", "къща", "кщъа"];
+        let mut tree = BkTree::new();
+        for word in words.iter() {
+            tree.insert(word);
+        }
+
+        for query in ["", "a", "ca", "tac", "kitten", "sittin", "къщ"].iter() {
+            for max_distance in 0..4 {
+                let mut expected = words
+                    .iter()
+                    .map(|word| (word.to_string(), distance(word, query)))
+                    .filter(|&(_, d)| d <= max_distance)
+                    .collect::<Vec<(String, usize)>>();
+                expected.sort_unstable_by(|(a, x), (b, y)| x.cmp(y).then_with(|| a.cmp(b)));
+
+                assert_eq!(tree.query(query, max_distance), expected, "{} {}", query, max_distance);
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::stable_hash::stable_hash;
+
+// A set which may answer "maybe" for words it does not contain,
+// but always answers "maybe" for words it does.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: usize,
+    hash_count: usize,
+}
+
+impl BloomFilter {
+    // Sized for `expected_words` with the given false positive rate.
+    pub fn new(expected_words: usize, false_positive_rate: f64) -> Self {
+        assert!(false_positive_rate > 0.0 && false_positive_rate < 1.0, "false positive rate must be in (0, 1)");
+        let ln2 = std::f64::consts::LN_2;
+        let n = expected_words.max(1) as f64;
+        let bit_count = (-n * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as usize;
+        let hash_count = ((bit_count as f64 / n) * ln2).round().max(1.0) as usize;
+        BloomFilter {
+            bits: vec![0; bit_count.div_ceil(64)],
+            bit_count,
+            hash_count,
+        }
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        for bit in self.bit_indices(word) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    pub fn might_contain(&self, word: &str) -> bool {
+        self.bit_indices(word).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    // Double hashing: the i-th index is h1 + i * h2.
+    fn bit_indices(&self, word: &str) -> impl Iterator<Item = usize> {
+        let (h1, h2) = (stable_hash(0, word), stable_hash(1, word) | 1);
+        let bit_count = self.bit_count as u64;
+        (0..self.hash_count as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bit_count) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bloom_filter_has_no_false_negatives() {
+        let words = (0..2000).map(|i| format!("word{}", i)).collect::<Vec<String>>();
+        let mut filter = BloomFilter::new(words.len(), 0.01);
+
+        for word in &words {
+            filter.insert(word);
+        }
+
+        assert!(words.iter().all(|word| filter.might_contain(word)));
+        let false_positives = (0..2000).filter(|i| filter.might_contain(&format!("other{}", i))).count();
+        assert!(false_positives < 100);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::spell_checker::SpellChecker;
+use crate::word_counter::WordCounter;
+use crate::bigram_counter::BigramCounter;
+use crate::keyboard::KeyboardLayout;
+
+pub struct SpellCheckerBuilder<'a> {
+    corpus: &'a str,
+    alphabet: String,
+    insert_alphabet: Option<String>,
+    replace_alphabet: Option<String>,
+    max_len_diff: Option<usize>,
+    max_rank_candidates: Option<usize>,
+    expected_unique_words: usize,
+    keyboard: Option<KeyboardLayout>,
+    alpha: f64,
+    error_model: Option<f64>,
+    boundary_inserts_only: bool,
+    #[cfg(feature = "unicode-segmentation")]
+    graphemes: bool,
+}
+
+impl<'a> SpellCheckerBuilder<'a> {
+    pub fn new(corpus: &'a str, alphabet: &str) -> Self {
+        SpellCheckerBuilder {
+            corpus,
+            alphabet: alphabet.to_owned(),
+            insert_alphabet: None,
+            replace_alphabet: None,
+            max_len_diff: None,
+            max_rank_candidates: None,
+            expected_unique_words: 0,
+            keyboard: None,
+            alpha: 1.0,
+            error_model: None,
+            boundary_inserts_only: false,
+            #[cfg(feature = "unicode-segmentation")]
+            graphemes: false,
+        }
+    }
+
+    // The letters tried by single inserts, the main alphabet by default.
+    pub fn insert_alphabet(mut self, alphabet: &str) -> Self {
+        self.insert_alphabet = Some(alphabet.to_owned());
+        self
+    }
+
+    // The letters tried by single replaces, the main alphabet by default.
+    pub fn replace_alphabet(mut self, alphabet: &str) -> Self {
+        self.replace_alphabet = Some(alphabet.to_owned());
+        self
+    }
+
+    // The largest length difference between a word and its candidates, unbounded by default.
+    pub fn max_len_diff(mut self, max_len_diff: usize) -> Self {
+        self.max_len_diff = Some(max_len_diff);
+        self
+    }
+
+    // How many candidates `correction` scores at most, unbounded by default.
+    // A cap bounds the latency but may miss the most probable candidate.
+    // A cap of 0 counts as 1, so there is always a candidate to return.
+    pub fn max_rank_candidates(mut self, max_rank_candidates: usize) -> Self {
+        self.max_rank_candidates = Some(max_rank_candidates.max(1));
+        self
+    }
+
+    // Pre-sizes the corpus for about `n` distinct words, avoiding rehashing.
+    pub fn expected_unique_words(mut self, n: usize) -> Self {
+        self.expected_unique_words = n;
+        self
+    }
+
+    // Prefers corrections that replace a key by a neighboring one, off by default.
+    pub fn keyboard_layout(mut self, layout: KeyboardLayout) -> Self {
+        self.keyboard = Some(layout);
+        self
+    }
+
+    // How much `correction` favors common words over similar ones, from 0.0
+    // to 1.0. The default 1.0 ranks by probability only.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    // Ranks the known words within two edits by a noisy channel model, where
+    // each edit multiplies the probability of a candidate by `edit_likelihood`,
+    // from 0.0 to 1.0. So a distance-1 word beats a distance-2 word unless
+    // it is more than 1 / `edit_likelihood` times less frequent. Off by default.
+    // With `alpha` below 1.0, the weighted probability is the one interpolated.
+    pub fn error_model(mut self, edit_likelihood: f64) -> Self {
+        self.error_model = Some(edit_likelihood.clamp(0.0, 1.0));
+        self
+    }
+
+    // Inserts letters only at the start and the end of words, off by default.
+    // This prunes most inserts of long words, but loses the one-edit
+    // corrections of words missing a letter in the middle.
+    pub fn boundary_inserts_only(mut self, enabled: bool) -> Self {
+        self.boundary_inserts_only = enabled;
+        self
+    }
+
+    // Edits grapheme clusters instead of chars, off by default.
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn graphemes(mut self, enabled: bool) -> Self {
+        self.graphemes = enabled;
+        self
+    }
+
+    pub fn build(self) -> SpellChecker {
+        let mut counter = WordCounter::with_capacity(self.expected_unique_words);
+        counter.add_text(self.corpus);
+        let mut checker = SpellChecker::from_corpus(counter, &self.alphabet)
+        .with_bigrams(BigramCounter::from_str(self.corpus));
+        if let Some(alphabet) = self.insert_alphabet {
+            checker.edits.insert_alphabet = alphabet;
+        }
+        if let Some(alphabet) = self.replace_alphabet {
+            checker.edits.replace_alphabet = alphabet;
+        }
+        if let Some(max_len_diff) = self.max_len_diff {
+            checker.max_len_diff = max_len_diff;
+        }
+        if let Some(max_rank_candidates) = self.max_rank_candidates {
+            checker.max_rank_candidates = max_rank_candidates;
+        }
+        checker.keyboard = self.keyboard;
+        checker.alpha = self.alpha;
+        checker.error_model = self.error_model;
+        checker.edits.boundary_inserts_only = self.boundary_inserts_only;
+        #[cfg(feature = "unicode-segmentation")]
+        {
+            checker.edits.graphemes = self.graphemes;
+        }
+        checker
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spell_checker::ALPHABET_EN;
+
+    #[test]
+    fn default_builder_matches_new() {
+        let built = SpellCheckerBuilder::new("ice isle spie crie dice mice mic", ALPHABET_EN).build();
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        assert_eq!(built.edits1("ide"), checker.edits1("ide"));
+        assert_eq!(built.correction("idde"), checker.correction("idde"));
+    }
+
+    #[test]
+    fn restricted_replace_alphabet() {
+        let corpus = "cat cat cut";
+        let checker = SpellCheckerBuilder::new(corpus, ALPHABET_EN).replace_alphabet("u").build();
+
+        assert_eq!(SpellChecker::new(corpus, ALPHABET_EN).correction("cxt"), "cat");
+        assert_eq!(checker.correction("cxt"), "cut");
+    }
+
+    #[test]
+    fn max_len_diff_excludes_far_shorter_candidates() {
+        let checker = SpellCheckerBuilder::new("cat", ALPHABET_EN).max_len_diff(1).build();
+
+        assert_eq!(SpellChecker::new("cat", ALPHABET_EN).correction("catss"), "cat");
+        assert_eq!(checker.correction("catss"), "catss");
+        assert_eq!(checker.correction("cats"), "cat");
+    }
+
+    #[test]
+    fn max_rank_candidates_may_miss_the_best_candidate() {
+        let corpus = "bat cat fat fat";
+        let checker = SpellCheckerBuilder::new(corpus, ALPHABET_EN).max_rank_candidates(2).build();
+
+        assert_eq!(SpellChecker::new(corpus, ALPHABET_EN).correction("xat"), "fat");
+        assert_eq!(checker.correction("xat"), "cat");
+    }
+
+    #[test]
+    fn zero_max_rank_candidates_scores_one_candidate() {
+        let checker = SpellCheckerBuilder::new("bat cat fat fat", ALPHABET_EN).max_rank_candidates(0).build();
+
+        assert_eq!(checker.correction("xat"), "bat");
+        assert_eq!(checker.try_correction("bat"), Some("bat".to_owned()));
+        assert_eq!(checker.correction("xyzzy"), "xyzzy");
+    }
+
+    #[test]
+    fn expected_unique_words_presizes_the_corpus() {
+        let corpus = "ice isle spie crie dice mice mic";
+        let built = SpellCheckerBuilder::new(corpus, ALPHABET_EN).expected_unique_words(500).build();
+        let checker = SpellChecker::new(corpus, ALPHABET_EN);
+
+        assert!(built.corpora[0].0.capacity() >= 500);
+        assert_eq!(built.correction("idde"), checker.correction("idde"));
+        assert_eq!(built.frequency("mice"), checker.frequency("mice"));
+    }
+
+    #[test]
+    fn lower_alpha_prefers_closer_candidates() {
+        let corpus = format!("cat {}", "crate ".repeat(200));
+        let build = |alpha| SpellCheckerBuilder::new(&corpus, ALPHABET_EN).alpha(alpha).build();
+
+        assert_eq!(build(1.0).correction("caat"), SpellChecker::new(&corpus, ALPHABET_EN).correction("caat"));
+        assert_eq!(build(1.0).correction("caat"), "cat");
+        assert_eq!(build(0.9).correction("caat"), "cat");
+        assert_eq!(build(0.1).correction("caat"), "cat");
+        assert_eq!(build(0.1).correction("xyzzy"), "xyzzy");
+    }
+
+    #[test]
+    fn boundary_inserts_only() {
+        let corpus = "spelling spell";
+        let pruned = SpellCheckerBuilder::new(corpus, ALPHABET_EN).boundary_inserts_only(true).build();
+        let checker = SpellChecker::new(corpus, ALPHABET_EN);
+
+        assert!(!pruned.edits1("speling").contains("spelling"));
+        assert!(pruned.edits1("pelling").contains("spelling"));
+        assert_eq!(pruned.correction("pelling"), "spelling");
+        assert!(pruned.edits1("speling").is_subset(&checker.edits1("speling")));
+        assert_eq!(checker.correction("speling"), "spelling");
+    }
+
+    #[test]
+    fn error_model_prefers_closer_candidates() {
+        let corpus = format!("cat {}", "crate ".repeat(50));
+        let build = |likelihood| SpellCheckerBuilder::new(&corpus, ALPHABET_EN).error_model(likelihood).build();
+
+        assert_eq!(build(0.01).correction("caat"), "cat");
+        assert_eq!(build(0.5).correction("caat"), "crate");
+        assert_eq!(build(0.01).correction("xyzzy"), "xyzzy");
+        assert_eq!(build(0.5).correction_posterior("caat")[0].0, "crate");
+    }
+
+    #[test]
+    fn error_model_applies_with_alpha() {
+        let corpus = format!("cat {}", "crate ".repeat(50));
+        let build = |alpha| SpellCheckerBuilder::new(&corpus, ALPHABET_EN).alpha(alpha).error_model(0.5).build();
+
+        let corrections = (0..=10)
+            .rev()
+            .map(|tenths| build(tenths as f64 / 10.0).correction("caat"))
+            .collect::<Vec<String>>();
+
+        assert_eq!(corrections.first().unwrap(), "crate");
+        assert_eq!(corrections.last().unwrap(), "cat");
+        let switch = corrections.iter().position(|c| c == "cat").unwrap();
+        assert!(corrections[switch..].iter().all(|c| c == "cat"));
+        assert_eq!(SpellCheckerBuilder::new(&corpus, ALPHABET_EN).alpha(0.9).error_model(0.01).build().correction("caat"), "cat");
+    }
+
+    #[test]
+    fn lower_alpha_moves_toward_more_similar_candidates() {
+        let corpus = format!("horse {}", "hoe ".repeat(20));
+        let build = |alpha| SpellCheckerBuilder::new(&corpus, ALPHABET_EN).alpha(alpha).build();
+
+        assert_eq!(build(1.0).correction("hose"), "hoe");
+        assert_eq!(build(0.99).correction("hose"), "hoe");
+        assert_eq!(build(0.0).correction("hose"), "horse");
+    }
+}
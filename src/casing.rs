@@ -0,0 +1,74 @@
+// The capitalization pattern of a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Casing {
+    Lower,
+    Title,
+    Upper,
+    // Anything else, e.g. "iPhone", which is not reapplied.
+    Mixed,
+}
+
+impl Casing {
+    // A single capital letter counts as Titlecase, so that "I" stays
+    // "I" and a longer correction of it is not shouted.
+    pub(crate) fn of(word: &str) -> Casing {
+        let letters = word.chars().filter(|c| c.is_alphabetic()).collect::<Vec<char>>();
+        let (first, rest) = match letters.split_first() {
+            Some(split) => split,
+            None => return Casing::Lower,
+        };
+        if letters.iter().all(|c| !c.is_uppercase()) {
+            Casing::Lower
+        }
+        else if first.is_uppercase() && rest.iter().all(|c| !c.is_uppercase()) {
+            Casing::Title
+        }
+        else if letters.iter().all(|c| !c.is_lowercase()) {
+            Casing::Upper
+        }
+        else {
+            Casing::Mixed
+        }
+    }
+
+    // Casing may change the length of a word, e.g. "ß" is uppercased to "SS".
+    pub(crate) fn apply(self, word: &str) -> String {
+        match self {
+            Casing::Lower => word.to_lowercase(),
+            Casing::Upper => word.to_uppercase(),
+            Casing::Title => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                    None => String::new(),
+                }
+            }
+            Casing::Mixed => word.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casing_of_words() {
+        assert_eq!(Casing::of("the"), Casing::Lower);
+        assert_eq!(Casing::of("The"), Casing::Title);
+        assert_eq!(Casing::of("THE"), Casing::Upper);
+        assert_eq!(Casing::of("iPhone"), Casing::Mixed);
+        assert_eq!(Casing::of("I"), Casing::Title);
+        assert_eq!(Casing::of("ДОМ"), Casing::Upper);
+        assert_eq!(Casing::of(""), Casing::Lower);
+    }
+
+    #[test]
+    fn apply_casing() {
+        assert_eq!(Casing::Title.apply("the"), "The");
+        assert_eq!(Casing::Upper.apply("straße"), "STRASSE");
+        assert_eq!(Casing::Title.apply("ßa"), "SSa");
+        assert_eq!(Casing::Title.apply("къща"), "Къща");
+        assert_eq!(Casing::Mixed.apply("the"), "the");
+    }
+}
@@ -0,0 +1,35 @@
+use crate::word_counter::WordCounter;
+
+pub trait Corpus {
+    fn get(&self, word: &str) -> u32;
+    fn total_count(&self) -> u32;
+
+    // The number of distinct words, 0 if the corpus cannot tell.
+    fn vocabulary_size(&self) -> usize {
+        0
+    }
+
+    fn probability(&self, word: &str) -> f64 {
+        let total = self.total_count();
+        if total > 0 {
+            self.get(word) as f64 / total as f64
+        }
+        else {
+            0.0
+        }
+    }
+}
+
+impl Corpus for WordCounter {
+    fn get(&self, word: &str) -> u32 {
+        WordCounter::get(self, word)
+    }
+
+    fn total_count(&self) -> u32 {
+        WordCounter::total_count(self)
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        WordCounter::vocabulary_size(self)
+    }
+}
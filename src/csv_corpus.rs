@@ -0,0 +1,95 @@
+use std::fmt;
+use std::io::Read;
+use crate::word_counter::WordCounter;
+
+#[derive(Debug)]
+pub enum CsvError {
+    Csv(csv::Error),
+    MissingColumn { row: usize, column: usize },
+    InvalidCount { row: usize, value: String },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::Csv(e) => write!(f, "malformed csv: {}", e),
+            CsvError::MissingColumn { row, column } => write!(f, "row {} has no column {}", row, column),
+            CsvError::InvalidCount { row, value } => write!(f, "row {} has an invalid count '{}'", row, value),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<csv::Error> for CsvError {
+    fn from(e: csv::Error) -> Self {
+        CsvError::Csv(e)
+    }
+}
+
+impl WordCounter {
+    // Reads a headerless CSV. Each row adds the word in `word_column`
+    // with the count in `count_column`, or once if there is no count column.
+    // Rows are indexed from 0.
+    pub fn from_csv<R: Read>(
+        reader: R,
+        word_column: usize,
+        count_column: Option<usize>,
+    ) -> Result<WordCounter, CsvError> {
+        let mut counter = WordCounter::new();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+        for (row, record) in reader.records().enumerate() {
+            let record = record?;
+            let field = |column| record.get(column).ok_or(CsvError::MissingColumn { row, column });
+            let word = field(word_column)?;
+            let count = match count_column {
+                Some(column) => {
+                    let value = field(column)?.trim();
+                    value.parse::<u32>().map_err(|_| CsvError::InvalidCount { row, value: value.to_owned() })?
+                }
+                None => 1,
+            };
+            counter.add_count(word, count);
+        }
+        Ok(counter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_csv_with_word_and_count_columns() {
+        let data = "1,the,120\n2,Cat,7\n3,sat, 3\n4,cat,1\n";
+
+        let counter = WordCounter::from_csv(data.as_bytes(), 1, Some(2)).unwrap();
+
+        assert_eq!(counter.words(), ["cat", "sat", "the"]);
+        assert_eq!(counter.get("the"), 120);
+        assert_eq!(counter.get("cat"), 8);
+        assert_eq!(counter.total_count(), 131);
+    }
+
+    #[test]
+    fn from_csv_without_count_column() {
+        let data = "the,x\ncat,y\nthe,z\n";
+
+        let counter = WordCounter::from_csv(data.as_bytes(), 0, None).unwrap();
+
+        assert_eq!(counter.get("the"), 2);
+        assert_eq!(counter.get("cat"), 1);
+    }
+
+    #[test]
+    fn from_csv_reports_malformed_rows() {
+        let missing = WordCounter::from_csv("the,1\ncat\n".as_bytes(), 0, Some(1));
+        let invalid = WordCounter::from_csv("the,1\ncat,2\nsat,many\n".as_bytes(), 0, Some(1));
+
+        assert!(matches!(missing, Err(CsvError::MissingColumn { row: 1, column: 1 })));
+        assert!(matches!(invalid, Err(CsvError::InvalidCount { row: 2, .. })));
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::{HashMap, HashSet};
+use crate::distance::unrestricted_damerau_levenshtein;
+
+// A SymSpell-style index from the strings with up to `max_distance` chars
+// deleted from the indexed words to those words. Two words within
+// `max_distance` edits of each other share such a delete, so a lookup only
+// generates the deletes of the query, never its inserts, replaces or transposes.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeleteIndex {
+    max_distance: usize,
+    deletes: HashMap<String, Vec<String>>,
+}
+
+impl DeleteIndex {
+    pub fn new(max_distance: usize) -> Self {
+        DeleteIndex {
+            max_distance,
+            deletes: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        for delete in deletes(word, self.max_distance) {
+            let words = self.deletes.entry(delete).or_default();
+            if !words.iter().any(|w| w == word) {
+                words.push(word.to_owned());
+            }
+        }
+    }
+
+    // The indexed words within `max_distance` edits of `word` paired with
+    // their distance, sorted by distance and then alphabetically.
+    pub fn lookup(&self, word: &str) -> Vec<(String, usize)> {
+        let mut seen = HashSet::new();
+        let mut result = deletes(word, self.max_distance)
+            .iter()
+            .filter_map(|delete| self.deletes.get(delete))
+            .flatten()
+            .filter(|candidate| seen.insert(candidate.as_str()))
+            .filter_map(|candidate| {
+                let distance = unrestricted_damerau_levenshtein(word, candidate);
+                if distance <= self.max_distance { Some((candidate.clone(), distance)) } else { None }
+            })
+            .collect::<Vec<(String, usize)>>();
+        result.sort_unstable_by(|(a, x), (b, y)| x.cmp(y).then_with(|| a.cmp(b)));
+        result
+    }
+}
+
+// `word` and the strings with up to `max_distance` of its chars deleted.
+fn deletes(word: &str, max_distance: usize) -> HashSet<String> {
+    let mut all = HashSet::new();
+    all.insert(word.to_owned());
+    let mut frontier = vec![word.to_owned()];
+    for _ in 0..max_distance {
+        let mut next = Vec::new();
+        for current in &frontier {
+            let chars = current.chars().collect::<Vec<char>>();
+            for i in 0..chars.len() {
+                let delete = chars[..i].iter().chain(&chars[i + 1..]).collect::<String>();
+                if all.insert(delete.clone()) {
+                    next.push(delete);
+                }
+            }
+        }
+        frontier = next;
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deletes_up_to_max_distance() {
+        let expected = ["abc", "ab", "ac", "bc", "a", "b", "c"].iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(deletes("abc", 2), expected);
+        assert_eq!(deletes("къща", 1).len(), 5);
+    }
+
+    #[test]
+    fn lookup_verifies_the_distance() {
+        let mut index = DeleteIndex::new(2);
+        for word in ["the", "tea", "to", "abcd", "cdab", "the"].iter() {
+            index.insert(word);
+        }
+
+        let found = index.lookup("teh");
+
+        assert_eq!(found, [("tea".to_owned(), 1), ("the".to_owned(), 1), ("to".to_owned(), 2)]);
+        assert!(index.lookup("badc").iter().all(|(word, _)| word != "cdab"));
+        assert!(DeleteIndex::new(2).lookup("the").is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+// The Levenshtein distance between `a` and `b` over chars, with
+// insertions, deletions and substitutions costing 1 each.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+// Like `edit_distance`, but a transposition of two adjacent chars costs 1,
+// as in the edits of the spell checker. Each substring is edited at most
+// once (the optimal string alignment distance).
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            let mut value = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(before_previous[j - 2] + 1);
+            }
+            current[j] = value;
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+// Like `damerau_levenshtein`, but without the restriction to edit each
+// substring once, so "ca" and "abc" are 2 apart. Unlike the restricted
+// distance, this one satisfies the triangle inequality.
+pub(crate) fn unrestricted_damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let (n, m) = (a.len(), b.len());
+    let far = n + m;
+    // Shifted by one, with an extra first row and column of `far`.
+    let mut table = vec![vec![far; m + 2]; n + 2];
+    for (i, row) in table.iter_mut().enumerate().skip(1) {
+        row[1] = i - 1;
+    }
+    for (j, cell) in table[1].iter_mut().enumerate().skip(1) {
+        *cell = j - 1;
+    }
+    // The last row of `a` where each char was seen.
+    let mut last_row = HashMap::new();
+    for i in 1..=n {
+        let mut last_match_column = 0;
+        for j in 1..=m {
+            let k = *last_row.get(&b[j - 1]).unwrap_or(&0);
+            let l = last_match_column;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_match_column = j;
+                0
+            }
+            else {
+                1
+            };
+            table[i + 1][j + 1] = (table[i][j] + cost)
+                .min(table[i + 1][j] + 1)
+                .min(table[i][j + 1] + 1)
+                .min(table[k][l] + (i - k - 1) + 1 + (j - l - 1));
+        }
+        last_row.insert(a[i - 1], i);
+    }
+    table[n + 1][m + 1]
+}
+
+// Whether the Levenshtein distance between `a` and `b` is at most `k`.
+// Only a band of width 2k + 1 around the diagonal is computed and
+// the computation stops as soon as a whole row exceeds `k`.
+pub fn within_distance(a: &str, b: &str, k: usize) -> bool {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let (n, m) = (a.len(), b.len());
+    if n.max(m) - n.min(m) > k {
+        return false;
+    }
+
+    let unreachable = k + 1;
+    let mut previous = (0..=m).map(|j| j.min(unreachable)).collect::<Vec<usize>>();
+    let mut current = vec![unreachable; m + 1];
+    for i in 1..=n {
+        let from = i.saturating_sub(k).max(1);
+        let to = (i + k).min(m);
+        current[0] = i.min(unreachable);
+        if from > 1 {
+            current[from - 1] = unreachable;
+        }
+        let mut row_min = current[0];
+        for j in from..=to {
+            let substitution = previous[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            let value = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+            current[j] = value.min(unreachable);
+            row_min = row_min.min(current[j]);
+        }
+        if to < m {
+            current[to + 1] = unreachable;
+        }
+        if row_min > k {
+            return false;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[m] <= k
+}
+
+// Accepts the words within Levenshtein distance `k` of a fixed word.
+// It is a deterministic automaton built once per word: each state is a row
+// of the edit distance table, with distances above `k` capped at k + 1, and
+// reading a char moves to the next row. The chars which are not in the word
+// all behave the same, so they share a single transition. Checking a
+// candidate then takes one table lookup per character of the candidate.
+pub struct LevenshteinAutomaton {
+    // The symbol of each distinct char of the word. Other chars have the
+    // symbol `symbol_count - 1`.
+    symbols: HashMap<char, usize>,
+    symbol_count: usize,
+    // The next state of `state` on `symbol` is at `state * symbol_count + symbol`.
+    transitions: Vec<usize>,
+    // The distance of the word to the chars read so far, if at most `k`.
+    distances: Vec<Option<usize>>,
+}
+
+// The state after which no candidate can be within `k` edits.
+const DEAD: usize = usize::MAX;
+
+impl LevenshteinAutomaton {
+    pub fn new(word: &str, k: usize) -> Self {
+        let word = word.chars().collect::<Vec<char>>();
+        let mut symbols = HashMap::new();
+        for &c in &word {
+            let next = symbols.len();
+            symbols.entry(c).or_insert(next);
+        }
+        let mut alphabet = vec![None; symbols.len() + 1];
+        for (&c, &symbol) in &symbols {
+            alphabet[symbol] = Some(c);
+        }
+
+        let unreachable = k + 1;
+        let first = (0..=word.len()).map(|j| j.min(unreachable)).collect::<Vec<usize>>();
+        let mut ids = HashMap::new();
+        ids.insert(first.clone(), 0);
+        let mut rows = vec![first];
+        let mut transitions = Vec::new();
+        let mut state = 0;
+        while state < rows.len() {
+            for c in &alphabet {
+                let row = &rows[state];
+                let mut next = vec![(row[0] + 1).min(unreachable); row.len()];
+                for j in 1..row.len() {
+                    let substitution = row[j - 1] + (Some(word[j - 1]) != *c) as usize;
+                    next[j] = substitution.min(row[j] + 1).min(next[j - 1] + 1).min(unreachable);
+                }
+                let target = if next.iter().all(|&distance| distance > k) {
+                    DEAD
+                }
+                else {
+                    let id = rows.len();
+                    *ids.entry(next.clone()).or_insert_with(|| {
+                        rows.push(next);
+                        id
+                    })
+                };
+                transitions.push(target);
+            }
+            state += 1;
+        }
+
+        LevenshteinAutomaton {
+            symbols,
+            symbol_count: alphabet.len(),
+            transitions,
+            distances: rows
+                .iter()
+                .map(|row| Some(row[word.len()]).filter(|&distance| distance <= k))
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, candidate: &str) -> bool {
+        self.distance(candidate).is_some()
+    }
+
+    // The distance between the word and `candidate`, if it is at most `k`.
+    pub fn distance(&self, candidate: &str) -> Option<usize> {
+        let mut state = 0;
+        for c in candidate.chars() {
+            let symbol = self.symbols.get(&c).copied().unwrap_or(self.symbol_count - 1);
+            state = self.transitions[state * self.symbol_count + symbol];
+            if state == DEAD {
+                return None;
+            }
+        }
+        self.distances[state]
+    }
+
+    // The number of states, excluding the dead one.
+    pub fn state_count(&self) -> usize {
+        self.distances.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_of_equal_and_empty_strings() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+    }
+
+    #[test]
+    fn edit_distance_counts_chars() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ab", "ba"), 2);
+        assert_eq!(edit_distance("къща", "къщи"), 1);
+        assert_eq!(edit_distance("ей", "йе"), 2);
+    }
+
+    #[test]
+    fn damerau_levenshtein_counts_transpositions_once() {
+        assert_eq!(damerau_levenshtein("ca", "ac"), 1);
+        assert_eq!(edit_distance("ca", "ac"), 2);
+        assert_eq!(damerau_levenshtein("teh", "the"), 1);
+        assert_eq!(damerau_levenshtein("къща", "кщъа"), 1);
+        assert_eq!(damerau_levenshtein("", "ab"), 2);
+        assert_eq!(damerau_levenshtein("kitten", "sitting"), 3);
+        assert_eq!(damerau_levenshtein("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn within_distance_of_equal_and_empty_strings() {
+        assert!(within_distance("", "", 0));
+        assert!(within_distance("abc", "abc", 0));
+        assert!(within_distance("", "abc", 3));
+        assert!(!within_distance("", "abc", 2));
+    }
+
+    #[test]
+    fn within_distance_thresholds() {
+        assert!(within_distance("kitten", "sitting", 3));
+        assert!(!within_distance("kitten", "sitting", 2));
+        assert!(within_distance("ей", "ейз", 1));
+        assert!(!within_distance("ca", "ac", 1));
+    }
+
+    #[test]
+    fn within_distance_rejects_very_different_lengths() {
+        let long = "a".repeat(100_000);
+
+        assert!(!within_distance("a", &long, 2));
+        assert!(!within_distance(&long, "b", 10));
+    }
+
+    #[test]
+    fn automaton_matches_exactly_the_words_within_distance() {
+        let words = ["", "a", "ab", "ba", "abc", "acb", "kitten", "sitting", "mitten", "bitte", "ей", "ейз", "зей"];
+
+        for word in words.iter() {
+            for k in 0..4 {
+                let automaton = LevenshteinAutomaton::new(word, k);
+                for candidate in words.iter() {
+                    let distance = edit_distance(word, candidate);
+                    let expected = if distance <= k { Some(distance) } else { None };
+
+                    assert_eq!(automaton.distance(candidate), expected, "{} {} {}", word, candidate, k);
+                    assert_eq!(automaton.matches(candidate), within_distance(word, candidate, k));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn automaton_states_are_shared() {
+        let automaton = LevenshteinAutomaton::new("aaaa", 1);
+
+        assert_eq!(automaton.state_count(), 10);
+        assert_eq!(automaton.distance("aaaa"), Some(0));
+        assert_eq!(automaton.distance("aaxa"), Some(1));
+        assert_eq!(automaton.distance("xaaxa"), None);
+        assert_eq!(automaton.distance(&"a".repeat(1000)), None);
+    }
+
+    #[test]
+    fn unrestricted_damerau_levenshtein_edits_substrings_more_than_once() {
+        assert_eq!(unrestricted_damerau_levenshtein("ca", "abc"), 2);
+        assert_eq!(unrestricted_damerau_levenshtein("teh", "the"), 1);
+        assert_eq!(unrestricted_damerau_levenshtein("", "ab"), 2);
+        assert_eq!(unrestricted_damerau_levenshtein("ab", ""), 2);
+        assert_eq!(unrestricted_damerau_levenshtein("kitten", "sitting"), 3);
+        assert_eq!(unrestricted_damerau_levenshtein("къща", "кщъа"), 1);
+
+        let words = ["", "a", "ab", "ba", "abc", "acb", "ca", "kitten", "sitting"];
+        for a in words.iter() {
+            for b in words.iter() {
+                assert!(unrestricted_damerau_levenshtein(a, b) <= damerau_levenshtein(a, b));
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+use std::path::Path;
+use encoding_rs::Encoding;
+use crate::word_counter::WordCounter;
+
+#[derive(Debug)]
+pub enum EncodingError {
+    Io(std::io::Error),
+    UnknownEncoding(String),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodingError::Io(e) => write!(f, "cannot read the corpus: {}", e),
+            EncodingError::UnknownEncoding(label) => write!(f, "unknown encoding '{}'", label),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+impl From<std::io::Error> for EncodingError {
+    fn from(e: std::io::Error) -> Self {
+        EncodingError::Io(e)
+    }
+}
+
+impl WordCounter {
+    // Transcodes the file from `encoding`, a label such as "windows-1251",
+    // to UTF-8. Malformed bytes become U+FFFD, which splits the words they
+    // occur in like any other non-letter. A byte order mark overrides `encoding`.
+    pub fn from_file_encoded<P: AsRef<Path>>(path: P, encoding: &str) -> Result<WordCounter, EncodingError> {
+        let encoding = Encoding::for_label(encoding.as_bytes())
+            .ok_or_else(|| EncodingError::UnknownEncoding(encoding.to_owned()))?;
+        let bytes = std::fs::read(path)?;
+        let (text, _, _) = encoding.decode(&bytes);
+        Ok(WordCounter::from_str(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cp1251_word_list() {
+        let path = std::env::temp_dir().join(format!("spell_checker_cp1251_{}.txt", std::process::id()));
+        // "къща\nКъща куче" in Windows-1251.
+        std::fs::write(&path, [0xEA, 0xFA, 0xF9, 0xE0, b'\n', 0xCA, 0xFA, 0xF9, 0xE0, b' ', 0xEA, 0xF3, 0xF7, 0xE5]).unwrap();
+
+        let counter = WordCounter::from_file_encoded(&path, "windows-1251").unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(counter.words(), ["куче", "къща"]);
+        assert_eq!(counter.get("къща"), 2);
+    }
+
+    #[test]
+    fn unknown_encoding() {
+        let result = WordCounter::from_file_encoded("corpus.txt", "no-such-encoding");
+
+        assert!(matches!(result, Err(EncodingError::UnknownEncoding(_))));
+    }
+}
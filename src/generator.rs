@@ -0,0 +1,328 @@
+use std::collections::HashSet;
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+use crate::corpus::Corpus;
+
+// Proposes candidate corrections for a word. The candidates of all
+// generators registered with a `SpellChecker` are ranked together.
+pub trait CandidateGenerator: Send + Sync {
+    fn candidates(&self, word: &str, corpus: &dyn Corpus) -> Vec<String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditOperation {
+    Delete,
+    Transpose,
+    Replace,
+    Insert,
+}
+
+// The built-in generator of words within a few edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EditGenerator {
+    pub(crate) insert_alphabet: String,
+    pub(crate) replace_alphabet: String,
+    // Whether letters are inserted only before or after the word.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) boundary_inserts_only: bool,
+    // Whether edits work on grapheme clusters instead of chars.
+    #[cfg(feature = "unicode-segmentation")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) graphemes: bool,
+}
+
+impl EditGenerator {
+    pub fn new(alphabet: &str) -> Self {
+        Self::with_alphabets(alphabet, alphabet)
+    }
+
+    pub fn with_alphabets(insert_alphabet: &str, replace_alphabet: &str) -> Self {
+        EditGenerator {
+            insert_alphabet: insert_alphabet.to_owned(),
+            replace_alphabet: replace_alphabet.to_owned(),
+            boundary_inserts_only: false,
+            #[cfg(feature = "unicode-segmentation")]
+            graphemes: false,
+        }
+    }
+
+    // Treats e.g. a letter and its combining accent as one editable unit.
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn with_graphemes(mut self, enabled: bool) -> Self {
+        self.graphemes = enabled;
+        self
+    }
+
+    // Inserts letters only at the start and the end of words, so a long
+    // word has far fewer inserts. Words missing a letter in the middle
+    // are then found two edits away or not at all.
+    pub fn with_boundary_inserts_only(mut self, enabled: bool) -> Self {
+        self.boundary_inserts_only = enabled;
+        self
+    }
+
+    pub fn edits1(&self, word: &str) -> HashSet<String> {
+        self.edits1_iter(word).collect()
+    }
+
+    // Like `edits1`, but yields the edits lazily: the deletes, then the
+    // transposes, the replaces and the inserts. An edit may be yielded
+    // more than once, e.g. when a letter is replaced with itself.
+    pub fn edits1_iter<'a>(&'a self, word: &'a str) -> impl Iterator<Item = String> + 'a {
+        let splits = self.unit_splits(word);
+        self.deletes(splits.clone())
+        .chain(self.transposes(splits.clone()))
+        .chain(self.replaces(splits.clone()))
+        .chain(self.inserts(splits))
+    }
+
+    pub fn edits1_by_operation(&self, word: &str) -> Vec<(EditOperation, Vec<String>)> {
+        let splits = self.unit_splits(word);
+        vec![
+            (EditOperation::Delete, self.single_deletes(&splits)),
+            (EditOperation::Transpose, self.adjacent_transposes(&splits)),
+            (EditOperation::Replace, self.single_replaces(&splits)),
+            (EditOperation::Insert, self.single_inserts(&splits)),
+        ]
+    }
+
+    pub(crate) fn single_deletes(&self, splits: &[(&str, &str)]) -> Vec<String> {
+        self.deletes(splits.iter().copied()).collect()
+    }
+
+    pub(crate) fn adjacent_transposes(&self, splits: &[(&str, &str)]) -> Vec<String> {
+        self.transposes(splits.iter().copied()).collect()
+    }
+
+    pub(crate) fn single_replaces(&self, splits: &[(&str, &str)]) -> Vec<String> {
+        self.replaces(splits.iter().copied()).collect()
+    }
+
+    pub(crate) fn single_inserts(&self, splits: &[(&str, &str)]) -> Vec<String> {
+        self.inserts(splits.iter().copied()).collect()
+    }
+
+    fn deletes<'a, I>(&'a self, splits: I) -> impl Iterator<Item = String> + 'a
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+        I::IntoIter: 'a
+    {
+        splits
+        .into_iter()
+        .filter(|(_, right)| !right.is_empty())
+        .map(move |(left, right)| {
+            format!("{}{}", left, &right[self.first_unit_len(right)..])
+        })
+    }
+
+    fn transposes<'a, I>(&'a self, splits: I) -> impl Iterator<Item = String> + 'a
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+        I::IntoIter: 'a
+    {
+        splits
+        .into_iter()
+        .filter_map(move |(left, right)| {
+            let first = self.first_unit_len(right);
+            let second = first + self.first_unit_len(&right[first..]);
+            if second > first && first > 0 {
+                Some(format!("{}{}{}{}", left, &right[first..second], &right[..first], &right[second..]))
+            }
+            else { None }
+        })
+    }
+
+    fn replaces<'a, I>(&'a self, splits: I) -> impl Iterator<Item = String> + 'a
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+        I::IntoIter: 'a
+    {
+        splits
+        .into_iter()
+        .filter(|(_, right)| !right.is_empty())
+        .flat_map(move |(left, right)| {
+            let rest = &right[self.first_unit_len(right)..];
+            self.replace_alphabet.chars().map(move |c| {
+                format!("{}{}{}", left, c, rest)
+            })
+        })
+    }
+
+    fn inserts<'a, I>(&'a self, splits: I) -> impl Iterator<Item = String> + 'a
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+        I::IntoIter: 'a
+    {
+        splits
+        .into_iter()
+        .filter(move |(left, right)| !self.boundary_inserts_only || left.is_empty() || right.is_empty())
+        .flat_map(move |(left, right)| {
+            self.insert_alphabet.chars().map(move |c| {
+                format!("{}{}{}", left, c, right)
+            })
+        })
+    }
+    
+    pub fn edits2(&self, word: &str) -> HashSet<String> {
+        self.edits1(word)
+        .into_iter()
+        .flat_map(|e1| self.edits1(&e1))
+        .collect()
+    }
+
+    // Like `edits2`, but edits the words one edit away in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn edits2_parallel(&self, word: &str) -> HashSet<String> {
+        self.edits_of(&self.edits1(word))
+    }
+
+    // The words which are exactly two edits away from `word`.
+    pub fn edits2_only(&self, word: &str) -> HashSet<String> {
+        let edits1 = self.edits1(word);
+        let mut result = self.edits_of(&edits1);
+        result.retain(|e2| !edits1.contains(e2));
+        result.remove(word);
+        result
+    }
+
+    #[cfg(feature = "parallel")]
+    fn edits_of(&self, words: &HashSet<String>) -> HashSet<String> {
+        use rayon::prelude::*;
+        words
+        .par_iter()
+        .flat_map_iter(|word| self.edits1_iter(word).collect::<Vec<String>>())
+        .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn edits_of(&self, words: &HashSet<String>) -> HashSet<String> {
+        words
+        .iter()
+        .flat_map(|word| self.edits1_iter(word))
+        .collect()
+    }
+
+    pub(crate) fn unit_splits<'a>(&self, word: &'a str) -> Vec<(&'a str, &'a str)> {
+        #[cfg(feature = "unicode-segmentation")]
+        if self.graphemes {
+            return word
+                .grapheme_indices(true)
+                .map(|(i, _)| (&word[..i], &word[i..]))
+                .chain(std::iter::once((word, "")))
+                .collect();
+        }
+        splits(word)
+    }
+
+    // The length in bytes of the first char or grapheme cluster of `s`.
+    fn first_unit_len(&self, s: &str) -> usize {
+        #[cfg(feature = "unicode-segmentation")]
+        if self.graphemes {
+            return s.graphemes(true).next().map_or(0, str::len);
+        }
+        s.chars().next().map_or(0, char::len_utf8)
+    }
+}
+
+impl CandidateGenerator for EditGenerator {
+    // The known words at the smallest edit distance, up to two.
+    fn candidates(&self, word: &str, corpus: &dyn Corpus) -> Vec<String> {
+        let known = |edits: HashSet<String>| {
+            let mut words = edits
+                .into_iter()
+                .filter(|edit| corpus.get(edit) > 0)
+                .collect::<Vec<String>>();
+            words.sort_unstable();
+            words
+        };
+        if corpus.get(word) > 0 {
+            return vec![word.to_owned()];
+        }
+        let words = known(self.edits1(word));
+        if !words.is_empty() {
+            return words;
+        }
+        known(self.edits2_only(word))
+    }
+}
+
+pub(crate) fn splits(word: &str) -> Vec<(&str, &str)> {
+    word
+    .char_indices()
+    .map(|(i, _)| (&word[..i], &word[i..]))
+    .chain([(word, "")].iter().copied())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word_counter::WordCounter;
+
+    #[test]
+    fn edit_generator_proposes_the_nearest_known_words() {
+        let generator = EditGenerator::new("abcdefghijklmnopqrstuvwxyz");
+        let corpus = WordCounter::from_str("ice isle spie crie dice mice mic");
+
+        assert_eq!(generator.candidates("mice", &corpus), ["mice"]);
+        assert_eq!(generator.candidates("ide", &corpus), ["ice"]);
+        assert_eq!(generator.candidates("idde", &corpus), ["dice", "ice", "isle"]);
+        assert!(generator.candidates("hamlet", &corpus).is_empty());
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn grapheme_clusters_are_edited_as_units() {
+        let word = "cafe\u{301}";
+        let chars = EditGenerator::new("abcdefghijklmnopqrstuvwxyz");
+        let graphemes = chars.clone().with_graphemes(true);
+        let deletes = |generator: &EditGenerator| generator.edits1_by_operation(word)[0].1.clone();
+
+        assert!(deletes(&chars).contains(&"cafe".to_owned()));
+        assert_eq!(deletes(&graphemes), ["afe\u{301}", "cfe\u{301}", "cae\u{301}", "caf"]);
+        assert!(graphemes.edits1(word).contains("cae\u{301}f"));
+        assert!(graphemes.edits1(word).contains("cafe"));
+        assert!(!graphemes.edits1(word).contains("caf\u{301}e"));
+    }
+
+    #[test]
+    fn edits1_iter_yields_the_edits1() {
+        let en = EditGenerator::new("abcdefghijklmnopqrstuvwxyz");
+        let bg = EditGenerator::with_alphabets("абв", "абвгд");
+
+        for word in ["", "a", "ab", "spelling"].iter() {
+            assert_eq!(en.edits1_iter(word).collect::<HashSet<String>>(), en.edits1(word));
+        }
+        for word in ["", "къща", "ей"].iter() {
+            assert_eq!(bg.edits1_iter(word).collect::<HashSet<String>>(), bg.edits1(word));
+        }
+        assert_eq!(en.edits1_iter("ab").next().as_deref(), Some("b"));
+        assert_eq!(en.edits1_iter("ab").count(), 2 + 1 + 2 * 26 + 3 * 26);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn edits2_parallel_matches_edits2() {
+        let en = EditGenerator::new("abc");
+        let bg = EditGenerator::new("абвгд");
+
+        assert_eq!(en.edits2_parallel("ab"), en.edits2("ab"));
+        assert_eq!(en.edits2_parallel(""), en.edits2(""));
+        assert_eq!(bg.edits2_parallel("къща"), bg.edits2("къща"));
+    }
+
+    #[test]
+    fn boundary_inserts_only() {
+        let generator = EditGenerator::new("xy").with_boundary_inserts_only(true);
+        let splits = generator.unit_splits("abc");
+
+        let inserts = generator.single_inserts(&splits);
+
+        assert_eq!(inserts, ["xabc", "yabc", "abcx", "abcy"]);
+        assert_eq!(EditGenerator::new("xy").single_inserts(&splits).len(), 8);
+        assert_eq!(generator.single_inserts(&generator.unit_splits("")), ["x", "y"]);
+        assert!(!generator.edits1("abc").contains("axbc"));
+        assert!(generator.edits1("abc").contains("bc"));
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use crate::corpus::Corpus;
+use crate::spell_checker::{SpellChecker, MAX_SUGGESTIONS};
+use crate::tokenizer;
+
+#[derive(Serialize)]
+struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Node {
+    Misspelling { id: String, word: String, line: usize, column: usize },
+    Candidate { id: String, word: String },
+}
+
+#[derive(Serialize)]
+struct Edge {
+    source: String,
+    target: String,
+    probability: f64,
+}
+
+impl<C: Corpus> SpellChecker<C> {
+    // Every misspelled token is a node connected to its top candidates.
+    // Candidate nodes are shared between the tokens which suggest them.
+    pub fn check_text_graph(&self, text: &str) -> String {
+        let mut graph = Graph { nodes: Vec::new(), edges: Vec::new() };
+        for token in tokenizer::tokenize(text) {
+            if self.is_correct(token.text) {
+                continue;
+            }
+            let id = format!("{}@{}:{}", token.text, token.line, token.column);
+            graph.nodes.push(Node::Misspelling {
+                id: id.clone(),
+                word: token.text.to_owned(),
+                line: token.line,
+                column: token.column,
+            });
+            let candidates = self.ranked_candidates(&token.text.to_lowercase());
+            for (candidate, probability) in candidates.into_iter().take(MAX_SUGGESTIONS) {
+                let target = format!("candidate:{}", candidate);
+                let exists = graph.nodes.iter().any(|node| match node {
+                    Node::Candidate { id, .. } => *id == target,
+                    _ => false,
+                });
+                if !exists {
+                    graph.nodes.push(Node::Candidate { id: target.clone(), word: candidate });
+                }
+                graph.edges.push(Edge { source: id.clone(), target, probability });
+            }
+        }
+        serde_json::to_string(&graph).expect("graph is serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spell_checker::{SpellChecker, ALPHABET_EN};
+
+    #[test]
+    fn check_text_graph_has_nodes_and_edges_for_typos() {
+        let checker = SpellChecker::new("the cat sat sat on a mat", ALPHABET_EN);
+
+        let json = checker.check_text_graph("the cst sat on a xat");
+        let graph: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let nodes = graph["nodes"].as_array().unwrap();
+        let edges = graph["edges"].as_array().unwrap();
+        let misspellings = nodes.iter().filter(|n| n["kind"] == "misspelling").collect::<Vec<_>>();
+        assert_eq!(misspellings.len(), 2);
+        assert_eq!(misspellings[0]["id"], "cst@0:4");
+        assert_eq!(misspellings[1]["word"], "xat");
+        assert_eq!(nodes.len(), 2 + 3);
+        assert_eq!(edges.len(), 1 + 3);
+        assert_eq!(edges[0]["source"], "cst@0:4");
+        assert_eq!(edges[0]["target"], "candidate:cat");
+        assert_eq!(edges[1]["target"], "candidate:sat");
+        assert!((edges[1]["probability"].as_f64().unwrap() - 2.0 / 7.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+// Which keys neighbor each other on a keyboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyboardLayout {
+    neighbors: HashMap<char, Vec<char>>,
+}
+
+impl KeyboardLayout {
+    // Each row is shifted by half a key to the right of the row above it,
+    // so a key touches two keys of the row above and two of the row below.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let rows = rows.iter().map(|row| row.chars().collect()).collect::<Vec<Vec<char>>>();
+        let mut neighbors: HashMap<char, Vec<char>> = HashMap::new();
+        for (r, row) in rows.iter().enumerate() {
+            for (c, &key) in row.iter().enumerate() {
+                let mut near = Vec::new();
+                let mut push = |row: Option<&Vec<char>>, column: Option<usize>| {
+                    if let Some(&neighbor) = row.zip(column).and_then(|(row, column)| row.get(column)) {
+                        near.push(neighbor);
+                    }
+                };
+                push(Some(row), c.checked_sub(1));
+                push(Some(row), Some(c + 1));
+                let above = r.checked_sub(1).map(|r| &rows[r]);
+                push(above, Some(c));
+                push(above, Some(c + 1));
+                let below = rows.get(r + 1);
+                push(below, c.checked_sub(1));
+                push(below, Some(c));
+                neighbors.insert(key, near);
+            }
+        }
+        KeyboardLayout { neighbors }
+    }
+
+    pub fn qwerty() -> Self {
+        Self::from_rows(&["qwertyuiop", "asdfghjkl", "zxcvbnm"])
+    }
+
+    pub fn are_adjacent(&self, a: char, b: char) -> bool {
+        self.neighbors.get(&a).is_some_and(|near| near.contains(&b))
+    }
+
+    // Whether `b` is `a` with one char replaced by a neighboring key.
+    pub fn is_adjacent_replace(&self, a: &str, b: &str) -> bool {
+        if a.chars().count() != b.chars().count() {
+            return false;
+        }
+        let mut differences = a.chars().zip(b.chars()).filter(|(x, y)| x != y);
+        match (differences.next(), differences.next()) {
+            (Some((x, y)), None) => self.are_adjacent(x, y),
+            _ => false,
+        }
+    }
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qwerty_neighbors() {
+        let layout = KeyboardLayout::qwerty();
+
+        for neighbor in "adwezx".chars() {
+            assert!(layout.are_adjacent('s', neighbor));
+        }
+        assert!(layout.are_adjacent('q', 'a'));
+        assert!(layout.are_adjacent('m', 'k'));
+        assert!(!layout.are_adjacent('s', 'q'));
+        assert!(!layout.are_adjacent('s', 'u'));
+        assert!(!layout.are_adjacent('s', 's'));
+    }
+
+    #[test]
+    fn adjacent_replaces() {
+        let layout = KeyboardLayout::qwerty();
+
+        assert!(layout.is_adjacent_replace("cst", "cat"));
+        assert!(!layout.is_adjacent_replace("cst", "cut"));
+        assert!(!layout.is_adjacent_replace("cat", "cat"));
+        assert!(!layout.is_adjacent_replace("csst", "cat"));
+        assert!(!layout.is_adjacent_replace("xsy", "cat"));
+    }
+}
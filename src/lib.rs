@@ -0,0 +1,142 @@
+pub mod word_counter;
+pub mod corpus;
+pub mod sketch_counter;
+pub mod quantized_counter;
+pub mod bigram_counter;
+pub mod timed_counter;
+pub mod spell_checker;
+pub mod builder;
+pub mod generator;
+pub mod tokenizer;
+pub mod distance;
+pub mod report;
+pub mod trace;
+pub mod number_words;
+pub mod keyboard;
+pub mod bloom;
+pub mod bk_tree;
+pub mod delete_index;
+pub mod trie;
+mod accents;
+mod stable_hash;
+mod casing;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "csv")]
+pub mod csv_corpus;
+#[cfg(feature = "encoding")]
+pub mod encoded_corpus;
+#[cfg(feature = "serde")]
+mod graph;
+#[cfg(feature = "serde")]
+pub mod persist;
+
+pub use crate::spell_checker::{ConfigError, Misspelling, SpellChecker, WordSwap, ALPHABET_BG, ALPHABET_EN, ALPHABET_HE};
+pub use crate::builder::SpellCheckerBuilder;
+pub use crate::generator::{CandidateGenerator, EditGenerator, EditOperation};
+pub use crate::report::SpellReport;
+pub use crate::trace::{CorrectionTrace, TracedCandidate};
+pub use crate::word_counter::WordCounter;
+pub use crate::corpus::Corpus;
+pub use crate::sketch_counter::SketchCounter;
+pub use crate::quantized_counter::QuantizedCounter;
+pub use crate::bigram_counter::BigramCounter;
+pub use crate::timed_counter::TimedCounter;
+pub use crate::number_words::Language;
+pub use crate::keyboard::KeyboardLayout;
+pub use crate::bloom::BloomFilter;
+pub use crate::bk_tree::BkTree;
+pub use crate::delete_index::DeleteIndex;
+pub use crate::trie::Trie;
+pub use crate::distance::{damerau_levenshtein, edit_distance, LevenshteinAutomaton};
+
+use std::borrow::Cow;
+
+// Idempotent, i.e. a cleaned line is returned unchanged.
+pub fn clean_line(input: &str) -> Cow<'_, str> {
+    let trimmed = input.trim();
+    if trimmed.chars().all(is_valid_symbol) {
+        Cow::Borrowed(trimmed)
+    }
+    else {
+        let cleaned = trimmed
+            .chars()
+            .filter(|&a| is_valid_symbol(a))
+            .collect::<String>();
+        Cow::Owned(cleaned.trim().to_owned())
+    }
+}
+
+// Like `clean_line`, but hyphens and apostrophes are kept
+// only when both of their neighbours are letters.
+pub fn clean_line_contextual(input: &str) -> String {
+    let chars = input
+        .chars()
+        .filter(|&a| is_valid_symbol(a))
+        .collect::<Vec<char>>();
+    let is_letter = |i: Option<&char>| i.is_some_and(|c| c.is_alphabetic());
+    let cleaned = chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            !(c == '-' || c == '\'') ||
+            (i > 0 && is_letter(chars.get(i - 1)) && is_letter(chars.get(i + 1)))
+        })
+        .map(|(_, &c)| c)
+        .collect::<String>();
+    cleaned.trim().to_owned()
+}
+
+fn is_valid_symbol(c: char) -> bool {
+    c == '-' ||
+    c == '\'' ||
+    c.is_alphabetic() ||
+    c.is_whitespace()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_line_with_already_cleaned_line() {
+        let line = "i'm a clean-mf-line";
+        assert_eq!(line, clean_line(line));
+    }
+    #[test]
+    fn clean_line_removes_leading_and_trailing_spaces() {
+        let line = " abc \n";        
+        assert_eq!(clean_line(line), "abc");
+    }
+    #[test]
+    fn clean_line_with_characters_to_remove() {
+        let line = "abc-1 @#";
+        assert_eq!(clean_line(line), "abc-");
+        assert_eq!(clean_line("1 abc 2"), "abc");
+    }
+    #[test]
+    fn clean_line_borrows_clean_input() {
+        assert!(matches!(clean_line(" abc-d'e "), Cow::Borrowed("abc-d'e")));
+        assert!(matches!(clean_line("abc1"), Cow::Owned(_)));
+    }
+    #[test]
+    fn clean_line_contextual_drops_edge_joiners() {
+        assert_eq!(clean_line_contextual("-abc-'"), "abc");
+        assert_eq!(clean_line_contextual(" don't - stop the well-known ' "), "don't  stop the well-known");
+        assert_eq!(clean_line_contextual("a-1b"), "a-b");
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn clean_line_is_idempotent(line in "\\PC*") {
+            let cleaned = clean_line(&line);
+            proptest::prop_assert_eq!(clean_line(&cleaned), cleaned.as_ref());
+        }
+
+        #[test]
+        fn clean_line_contextual_is_idempotent(line in "[a-zа-я '\\-1.\\t]*") {
+            let cleaned = clean_line_contextual(&line);
+            proptest::prop_assert_eq!(clean_line_contextual(&cleaned), cleaned.as_str());
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+use crate::corpus::Corpus;
+use crate::spell_checker::SpellChecker;
+
+pub const SEVERITY_INFORMATION: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LspDiagnostic {
+    pub range: Range,
+    pub severity: u8,
+    pub message: String,
+    pub suggestions: Vec<String>,
+}
+
+impl<C: Corpus> SpellChecker<C> {
+    // Positions are zero-based and count UTF-16 code units within a line,
+    // the default position encoding of the protocol.
+    pub fn check_text_lsp(&self, text: &str) -> Vec<LspDiagnostic> {
+        self.check_text(text)
+        .into_iter()
+        .map(|misspelling| {
+            let line_start = text[..misspelling.start].rfind('\n').map_or(0, |i| i + 1);
+            let character = utf16_len(&text[line_start..misspelling.start]);
+            let start = Position { line: misspelling.line, character };
+            let end = Position {
+                line: misspelling.line,
+                character: character + utf16_len(&misspelling.word),
+            };
+            let message = match misspelling.suggestions.first() {
+                Some(suggestion) => format!("Did you mean '{}'?", suggestion),
+                None => format!("Unknown word '{}'.", misspelling.word),
+            };
+            LspDiagnostic {
+                range: Range { start, end },
+                severity: SEVERITY_INFORMATION,
+                message,
+                suggestions: misspelling.suggestions,
+            }
+        })
+        .collect()
+    }
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.chars().map(char::len_utf16).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spell_checker::ALPHABET_EN;
+
+    #[test]
+    fn check_text_lsp_with_two_typos() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+
+        let diagnostics = checker.check_text_lsp("the cst\n  sat on a mmat");
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].range, Range {
+            start: Position { line: 0, character: 4 },
+            end: Position { line: 0, character: 7 },
+        });
+        assert_eq!(diagnostics[0].message, "Did you mean 'cat'?");
+        assert_eq!(diagnostics[1].range, Range {
+            start: Position { line: 1, character: 11 },
+            end: Position { line: 1, character: 15 },
+        });
+        assert_eq!(diagnostics[1].suggestions, ["mat"]);
+    }
+
+    #[test]
+    fn check_text_lsp_counts_utf16_code_units() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+
+        let diagnostics = checker.check_text_lsp("the cat\n\u{1F600} cst");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, Range {
+            start: Position { line: 1, character: 3 },
+            end: Position { line: 1, character: 6 },
+        });
+    }
+
+    #[test]
+    fn lsp_diagnostic_json() {
+        let checker = SpellChecker::new("the cat", ALPHABET_EN);
+
+        let json = serde_json::to_value(checker.check_text_lsp("cst")).unwrap();
+
+        assert_eq!(json, serde_json::json!([{
+            "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 0, "character": 3 },
+            },
+            "severity": 3,
+            "message": "Did you mean 'cat'?",
+            "suggestions": ["cat"],
+        }]));
+    }
+}
@@ -1,41 +1,82 @@
-mod word_counter;
-mod spell_checker;
+use std::io::{self, BufRead, Write};
+use std::process;
+use clap::{Parser, ValueEnum};
+use spell_checker::{SpellChecker, ALPHABET_BG, ALPHABET_EN};
 
-pub fn clean_line(input: &str) -> String {
-    input
-    .trim()
-    .chars()
-    .filter(|&a| is_valid_symbol(a))
-    .collect()
+#[derive(Clone, Copy, ValueEnum)]
+enum Alphabet {
+    En,
+    Bg,
 }
 
-fn is_valid_symbol(c: char) -> bool {
-    c == '-' ||
-    c == '\'' ||
-    c.is_alphabetic() ||
-    c.is_whitespace()
+#[derive(Parser)]
+#[command(version, about = "Prints each word and its correction, separated by a tab")]
+struct Args {
+    #[arg(long, help = "The text file to learn the known words from")]
+    corpus: String,
+    #[arg(long, value_enum, default_value = "en", help = "The alphabet of the corpus")]
+    alphabet: Alphabet,
+    #[arg(long, conflicts_with = "words", help = "Read one word per line and print its correction right away")]
+    interactive: bool,
+    #[arg(help = "The words to correct, read from stdin if there are none")]
+    words: Vec<String>,
 }
 
 fn main() {
-}
+    let args = Args::parse();
+    let corpus = std::fs::read_to_string(&args.corpus).unwrap_or_else(|e| {
+        eprintln!("error: cannot read the corpus '{}': {}", args.corpus, e);
+        process::exit(1);
+    });
+    let alphabet = match args.alphabet {
+        Alphabet::En => ALPHABET_EN,
+        Alphabet::Bg => ALPHABET_BG,
+    };
+    let checker = SpellChecker::new(&corpus, alphabet);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    let result = if args.interactive {
+        interactive(&checker)
+    }
+    else {
+        correct_all(&checker, &args.words)
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
 
-    #[test]
-    fn clean_line_with_already_cleaned_line() {
-        let line = "i'm a clean-mf-line";
-        assert_eq!(line, clean_line(line));
+fn correct_all(checker: &SpellChecker, words: &[String]) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut print = |word: &str| writeln!(out, "{}\t{}", word, checker.correction(word));
+    if words.is_empty() {
+        io::stdin()
+        .lock()
+        .lines()
+        .try_for_each(|line| {
+            line?
+            .split_whitespace()
+            .try_for_each(&mut print)
+        })
     }
-    #[test]
-    fn clean_line_removes_leading_and_trailing_spaces() {
-        let line = " abc \n";        
-        assert_eq!(clean_line(line), "abc");
+    else {
+        words.iter().try_for_each(|word| print(word))
     }
-    #[test]
-    fn clean_line_with_characters_to_remove() {
-        let line = "abc-1 @#";
-        assert_eq!(clean_line(line), "abc- ");
+}
+
+// Skips empty lines and flushes after each correction, for use in pipes.
+fn interactive(checker: &SpellChecker) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let word = line.trim();
+        if word.is_empty() {
+            continue;
+        }
+        writeln!(out, "{}", checker.correction(word))?;
+        out.flush()?;
     }
-}
\ No newline at end of file
+    Ok(())
+}
@@ -0,0 +1,31 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Bulgarian,
+}
+
+const NUMBER_WORDS_EN: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen",
+    "seventeen", "eighteen", "nineteen", "twenty", "thirty", "forty", "fifty",
+    "sixty", "seventy", "eighty", "ninety", "hundred", "thousand", "million",
+    "billion", "first", "second", "third", "fourth", "fifth", "sixth", "seventh",
+    "eighth", "ninth", "tenth",
+];
+
+const NUMBER_WORDS_BG: &[&str] = &[
+    "нула", "едно", "един", "една", "две", "два", "три", "четири", "пет", "шест",
+    "седем", "осем", "девет", "десет", "единадесет", "дванадесет", "тринадесет",
+    "четиринадесет", "петнадесет", "шестнадесет", "седемнадесет", "осемнадесет",
+    "деветнадесет", "двадесет", "тридесет", "четиридесет", "петдесет", "шестдесет",
+    "седемдесет", "осемдесет", "деветдесет", "сто", "хиляда", "хиляди", "милион",
+    "милиард", "първи", "втори", "трети", "четвърти", "пети", "шести", "седми",
+    "осми", "девети", "десети",
+];
+
+pub fn number_words(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::English => NUMBER_WORDS_EN,
+        Language::Bulgarian => NUMBER_WORDS_BG,
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::spell_checker::SpellChecker;
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(std::io::Error),
+    Format(bincode::Error),
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PersistError::Io(e) => write!(f, "cannot access the model file: {}", e),
+            PersistError::Format(e) => write!(f, "malformed model: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<std::io::Error> for PersistError {
+    fn from(e: std::io::Error) -> Self {
+        PersistError::Io(e)
+    }
+}
+
+impl From<bincode::Error> for PersistError {
+    fn from(e: bincode::Error) -> Self {
+        PersistError::Format(e)
+    }
+}
+
+// Registered candidate generators are not saved.
+impl<C: Serialize + DeserializeOwned> SpellChecker<C> {
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, PersistError> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(bincode::deserialize_from(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spell_checker::{SpellChecker, ALPHABET_EN};
+
+    #[test]
+    fn saved_checker_corrects_the_same() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic the the cat", ALPHABET_EN);
+        let path = std::env::temp_dir().join(format!("spell_checker_model_{}.bin", std::process::id()));
+
+        checker.save_to_file(&path).unwrap();
+        let loaded: SpellChecker = SpellChecker::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for word in ["ide", "idde", "teh", "mice", "xyzzy", "ct"].iter() {
+            assert_eq!(loaded.correction(word), checker.correction(word));
+        }
+        assert_eq!(loaded.alphabet, checker.alphabet);
+        assert_eq!(loaded.frequency("the"), checker.frequency("the"));
+    }
+
+    #[test]
+    fn saved_file_holds_the_whole_model() {
+        let corpus = "the quick brown fox jumps over the lazy dog ".repeat(500);
+        let checker = SpellChecker::new(&corpus, ALPHABET_EN);
+        let path = std::env::temp_dir().join(format!("spell_checker_bytes_{}.bin", std::process::id()));
+
+        checker.save_to_file(&path).unwrap();
+        let saved = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved, bincode::serialize(&checker).unwrap());
+    }
+
+    #[test]
+    fn loading_a_missing_file_fails() {
+        let result = SpellChecker::<crate::WordCounter>::load_from_file("/nonexistent/model.bin");
+
+        assert!(matches!(result, Err(super::PersistError::Io(_))));
+    }
+}
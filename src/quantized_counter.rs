@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use crate::corpus::Corpus;
+
+// Levels per natural-log unit: each level is a ~13% step in count.
+const LEVELS_PER_UNIT: f64 = 8.0;
+
+// A read-only counter that keeps one byte of log-frequency per word.
+// Counts are approximate, but their order is preserved up to ties
+// between words whose counts fall in the same level.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantizedCounter {
+    levels: HashMap<String, u8>,
+    total: u32,
+}
+
+impl QuantizedCounter {
+    pub(crate) fn from_counts<'a, I>(counts: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a String, &'a u32)>
+    {
+        let levels = counts
+        .into_iter()
+        .filter(|(_, &count)| count > 0)
+        .map(|(word, &count)| (word.clone(), quantize(count)))
+        .collect::<HashMap<String, u8>>();
+        let total = levels
+        .values()
+        .fold(0u32, |total, &level| total.saturating_add(dequantize(level)));
+        QuantizedCounter { levels, total }
+    }
+
+    pub fn get(&self, word: &str) -> u32 {
+        self.levels.get(word).map_or(0, |&level| dequantize(level))
+    }
+
+    pub fn total_count(&self) -> u32 {
+        self.total
+    }
+}
+
+impl Corpus for QuantizedCounter {
+    fn get(&self, word: &str) -> u32 {
+        QuantizedCounter::get(self, word)
+    }
+
+    fn total_count(&self) -> u32 {
+        QuantizedCounter::total_count(self)
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        self.levels.len()
+    }
+}
+
+fn quantize(count: u32) -> u8 {
+    ((count as f64).ln() * LEVELS_PER_UNIT).round().min(u8::MAX as f64) as u8
+}
+
+fn dequantize(level: u8) -> u32 {
+    (level as f64 / LEVELS_PER_UNIT).exp().round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::word_counter::WordCounter;
+
+    #[test]
+    fn quantized_counts_are_close() {
+        let counter = WordCounter::from_str(&"a ".repeat(1000));
+
+        let quantized = counter.quantized();
+
+        assert_eq!(quantized.get("b"), 0);
+        let estimate = quantized.get("a") as f64;
+        assert!((estimate - 1000.0).abs() / 1000.0 < 0.07);
+        assert_eq!(quantized.total_count(), quantized.get("a"));
+    }
+
+    #[test]
+    fn quantization_mostly_preserves_ranking() {
+        let mut text = String::new();
+        for i in 1..=60 {
+            let word = format!("{}{} ", (b'a' + (i / 26) as u8) as char, (b'a' + (i % 26) as u8) as char);
+            text.push_str(&word.repeat(i * i));
+        }
+        let exact = WordCounter::from_str(&text);
+
+        let quantized = exact.quantized();
+
+        let words = exact.words();
+        assert_eq!(words.len(), 60);
+        let mut pairs = 0;
+        let mut ties = 0;
+        for a in &words {
+            for b in &words {
+                if exact.get(a) > exact.get(b) {
+                    pairs += 1;
+                    assert!(quantized.get(a) >= quantized.get(b));
+                    if quantized.get(a) == quantized.get(b) {
+                        ties += 1;
+                    }
+                }
+            }
+        }
+        assert!(ties * 10 < pairs);
+    }
+
+    #[test]
+    fn quantized_as_spell_checker_corpus() {
+        let counter = WordCounter::from_str("ice ice isle spie crie dice mice mic");
+        let checker = crate::SpellChecker::from_corpus(counter.quantized(), crate::ALPHABET_EN);
+
+        assert_eq!(checker.correction("ide"), "ice");
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use crate::corpus::Corpus;
+use crate::spell_checker::SpellChecker;
+use crate::tokenizer;
+
+const TOP_MISSPELLINGS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellReport {
+    pub total_tokens: usize,
+    pub unique_tokens: usize,
+    pub misspelled_count: usize,
+    pub misspelling_rate: f64,
+    // The most frequent misspelled words with their count and correction.
+    pub top_misspellings: Vec<(String, usize, String)>,
+}
+
+impl<C: Corpus> SpellChecker<C> {
+    pub fn report(&self, text: &str) -> SpellReport {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let tokens = tokenizer::tokenize(text);
+        for token in &tokens {
+            *counts.entry(token.text.to_lowercase()).or_insert(0) += 1;
+        }
+        let mut misspellings = counts
+            .iter()
+            .filter(|(word, _)| !self.is_correct(word))
+            .map(|(word, &count)| (word.clone(), count))
+            .collect::<Vec<(String, usize)>>();
+        let misspelled_count = misspellings.iter().map(|(_, count)| count).sum::<usize>();
+        misspellings.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+
+        SpellReport {
+            total_tokens: tokens.len(),
+            unique_tokens: counts.len(),
+            misspelled_count,
+            misspelling_rate: rate(misspelled_count, tokens.len()),
+            top_misspellings: misspellings
+                .into_iter()
+                .take(TOP_MISSPELLINGS)
+                .map(|(word, count)| {
+                    let correction = self.correction(&word);
+                    (word, count, correction)
+                })
+                .collect(),
+        }
+    }
+
+    // The fraction of words in the text which are misspelled.
+    pub fn misspelling_rate(&self, text: &str) -> f64 {
+        let tokens = tokenizer::tokenize(text);
+        let misspelled = tokens.iter().filter(|token| !self.is_correct(token.text)).count();
+        rate(misspelled, tokens.len())
+    }
+}
+
+fn rate(part: usize, total: usize) -> f64 {
+    if total > 0 { part as f64 / total as f64 } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spell_checker::ALPHABET_EN;
+
+    #[test]
+    fn report_of_a_small_document() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+        let text = "The cst sat on a mat. The cst, the xyzzy and the mst.";
+
+        let report = checker.report(text);
+
+        assert_eq!(report.total_tokens, 13);
+        assert_eq!(report.unique_tokens, 9);
+        assert_eq!(report.misspelled_count, 5);
+        assert!((report.misspelling_rate - 5.0 / 13.0).abs() < 1e-9);
+        assert_eq!(report.top_misspellings, [
+            ("cst".to_owned(), 2, "cat".to_owned()),
+            ("and".to_owned(), 1, "on".to_owned()),
+            ("mst".to_owned(), 1, "mat".to_owned()),
+            ("xyzzy".to_owned(), 1, "xyzzy".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn misspelling_rate() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+
+        assert_eq!(checker.misspelling_rate("the cst sat on a mqt"), 2.0 / 6.0);
+        assert_eq!(checker.misspelling_rate("the cat"), 0.0);
+        assert_eq!(checker.misspelling_rate(""), 0.0);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::corpus::Corpus;
+use crate::stable_hash::stable_hash;
+use crate::word_counter::corpus_words;
+
+// A count-min sketch: approximate word counts in `width * depth` cells.
+// Estimates never undercount and overcount by at most
+// `e / width * total_count` with probability `1 - exp(-depth)`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SketchCounter {
+    width: usize,
+    depth: usize,
+    cells: Vec<u32>,
+    total: u32,
+}
+
+impl SketchCounter {
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0, "sketch dimensions must be positive");
+        SketchCounter {
+            width,
+            depth,
+            cells: vec![0; width * depth],
+            total: 0,
+        }
+    }
+
+    pub fn from_str(input: &str, width: usize, depth: usize) -> Self {
+        let mut counter = Self::new(width, depth);
+        for word in corpus_words(input) {
+            counter.add(&word);
+        }
+        counter
+    }
+
+    pub fn add(&mut self, item: &str) {
+        let word = item.trim().to_lowercase();
+        for row in 0..self.depth {
+            let i = self.cell(row, &word);
+            self.cells[i] = self.cells[i].saturating_add(1);
+        }
+        self.total += 1;
+    }
+
+    pub fn get(&self, word: &str) -> u32 {
+        (0..self.depth)
+        .map(|row| self.cells[self.cell(row, word)])
+        .min()
+        .unwrap_or(0)
+    }
+
+    pub fn total_count(&self) -> u32 {
+        self.total
+    }
+
+    fn cell(&self, row: usize, word: &str) -> usize {
+        row * self.width + (stable_hash(row as u64, word) % self.width as u64) as usize
+    }
+}
+
+impl Corpus for SketchCounter {
+    fn get(&self, word: &str) -> u32 {
+        SketchCounter::get(self, word)
+    }
+
+    fn total_count(&self) -> u32 {
+        SketchCounter::total_count(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::word_counter::WordCounter;
+
+    #[test]
+    fn empty_sketch() {
+        let counter = SketchCounter::new(16, 4);
+
+        assert_eq!(counter.get("random"), 0);
+        assert_eq!(counter.total_count(), 0);
+    }
+
+    #[test]
+    fn estimates_are_within_error_bounds() {
+        let mut text = String::new();
+        for i in 0..200 {
+            text.push_str(&format!("the a of rare{} ", i % 50));
+        }
+        let exact = WordCounter::from_str(&text);
+        let (width, depth) = (256, 4);
+
+        let sketch = SketchCounter::from_str(&text, width, depth);
+
+        let bound = (std::f64::consts::E / width as f64 * exact.total_count() as f64) as u32;
+        for word in exact.words() {
+            let estimate = sketch.get(word);
+            assert!(estimate >= exact.get(word));
+            assert!(estimate <= exact.get(word) + bound);
+        }
+        assert!(sketch.get("the") > sketch.get("rare7"));
+        assert_eq!(sketch.total_count(), exact.total_count());
+    }
+
+    #[test]
+    fn sketch_as_spell_checker_corpus() {
+        let sketch = SketchCounter::from_str("ice isle spie crie dice mice mic", 64, 4);
+        let checker = crate::SpellChecker::from_corpus(sketch, crate::ALPHABET_EN);
+
+        assert_eq!(checker.correction("ide"), "ice");
+    }
+}
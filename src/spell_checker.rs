@@ -1,312 +1,3007 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::OnceLock;
 use crate::word_counter::WordCounter;
+use crate::bigram_counter::BigramCounter;
+use crate::corpus::Corpus;
+use crate::builder::SpellCheckerBuilder;
+use crate::accents::fold_accents;
+use crate::casing::Casing;
+use crate::generator::{CandidateGenerator, EditGenerator, EditOperation};
+use crate::tokenizer;
+use crate::keyboard::KeyboardLayout;
+use crate::bloom::BloomFilter;
+use crate::bk_tree::BkTree;
+use crate::delete_index::DeleteIndex;
+use crate::trie::Trie;
+use crate::distance::{damerau_levenshtein, LevenshteinAutomaton};
+use crate::number_words::{self, Language};
 
-pub const ALPHABET_EN: &'static str = "abcdefghijklmnopqrstuvwxyz";
-pub const ALPHABET_BG: &'static str = "абвгдежзийклмнопрстуфхцчшщъьюя";
+pub const ALPHABET_EN: &str = "abcdefghijklmnopqrstuvwxyz";
+pub const ALPHABET_BG: &str = "абвгдежзийклмнопрстуфхцчшщъьюя";
+pub const ALPHABET_HE: &str = "אבגדהוזחטיךכלםמןנסעףפץצקרשת";
 
-pub struct SpellChecker {
-    corpus: WordCounter,
-    alphabet: String,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    EmptyCorpus,
+    NoAlphabetOverlap,
+    ConflictingOptions,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::EmptyCorpus => write!(f, "the corpus contains no words"),
+            ConfigError::NoAlphabetOverlap => write!(f, "no corpus word uses a letter of the alphabet"),
+            ConfigError::ConflictingOptions => write!(f, "the checkers have conflicting options"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+pub(crate) const MAX_SUGGESTIONS: usize = 5;
+// How many times more frequent the swapped order of two words must be.
+const SWAP_RATIO: f64 = 10.0;
+// How many times more likely in its context an alternative of a known word
+// must be for `check_real_words` to report the word.
+const REAL_WORD_RATIO: f64 = 10.0;
+// The default likelihood of each edit in the error model of `correction_posterior`.
+const EDIT_LIKELIHOOD: f64 = 0.01;
+// How much more likely a typo on a neighboring key is.
+const ADJACENT_KEY_BONUS: f64 = 2.0;
+// The weight of the probabilities within the checked document in `check_text`.
+const LOCAL_WEIGHT: f64 = 0.5;
+// The weight of P(word | previous word) against P(word) in `correct_in_context`.
+const CONTEXT_WEIGHT: f64 = 0.9;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub word: String,
+    pub line: usize,
+    // In chars from the start of the line, in logical (not visual) order,
+    // so right-to-left text is counted from its first written char.
+    pub column: usize,
+    // The byte span of the word in the checked text.
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+}
+
+// Two adjacent words which are likely written in the wrong order,
+// i.e. `second first` was meant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSwap {
+    pub first: String,
+    pub second: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpellChecker<C = WordCounter> {
+    pub(crate) corpora: Vec<(C, f64)>,
+    pub(crate) alphabet: String,
+    pub(crate) edits: EditGenerator,
+    pub(crate) bigrams: BigramCounter,
+    // Personal words, always correct and boosted in the rankings.
+    user_dict: WordCounter,
+    user_dict_boost: f64,
+    // Whether probabilities use add-one smoothing.
+    smoothing: bool,
+    // The corpus words, for a fast rejection of unknown words.
+    bloom: Option<BloomFilter>,
+    // Known words whose length differs from the input by more are not candidates.
+    pub(crate) max_len_diff: usize,
+    pub(crate) max_rank_candidates: usize,
+    // Boosts candidates one neighboring-key replace away, if set.
+    pub(crate) keyboard: Option<KeyboardLayout>,
+    // The weight of the probability against the similarity in `correction`.
+    pub(crate) alpha: f64,
+    // The likelihood of each edit in the error model of `correction`, if set.
+    pub(crate) error_model: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    generators: Vec<Box<dyn CandidateGenerator>>,
+    // Accent-folded forms of the corpus words, empty unless accent-insensitive.
+    accent_index: HashMap<String, Vec<String>>,
+    // The vocabulary for `bk_candidates`, built on first use.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    bk_tree: OnceLock<BkTree>,
+    // Replaces the edits in the search for the nearest known words, if set.
+    delete_index: Option<DeleteIndex>,
+    // The corpus words for `completions`, built on first use.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    trie: OnceLock<Trie>,
 }
 
 impl SpellChecker {
     pub fn new(corpus: &str, alphabet: &str) -> Self {
+        Self::from_corpus(WordCounter::from_str(corpus), alphabet)
+        .with_bigrams(BigramCounter::from_str(corpus))
+    }
+
+    // The alphabet is the union of `alphabets`, in order and without duplicates.
+    pub fn new_multilingual(corpus: &str, alphabets: &[&str]) -> Self {
+        let mut alphabet = String::new();
+        for c in alphabets.iter().flat_map(|alphabet| alphabet.chars()) {
+            if !alphabet.contains(c) {
+                alphabet.push(c);
+            }
+        }
+        Self::new(corpus, &alphabet)
+    }
+
+    // The alphabet is made of the distinct letters of the corpus words, sorted.
+    pub fn from_corpus_auto(corpus: &str) -> Self {
+        let counter = WordCounter::from_str(corpus);
+        let alphabet = counter
+            .words()
+            .iter()
+            .flat_map(|word| word.chars())
+            .filter(|c| c.is_alphabetic())
+            .collect::<BTreeSet<char>>()
+            .into_iter()
+            .collect::<String>();
+        Self::from_corpus(counter, &alphabet)
+        .with_bigrams(BigramCounter::from_str(corpus))
+    }
+
+    pub fn builder<'a>(corpus: &'a str, alphabet: &str) -> SpellCheckerBuilder<'a> {
+        SpellCheckerBuilder::new(corpus, alphabet)
+    }
+
+    // Each number word missing from the corpus is counted as if it
+    // was seen once in the primary corpus.
+    pub fn with_number_words(mut self, language: Language) -> Self {
+        let missing = number_words::number_words(language)
+            .iter()
+            .filter(|word| !self.is_known(word))
+            .collect::<Vec<_>>();
+        for word in missing {
+            self.learn(word);
+        }
+        self
+    }
+
+    // Adds an occurrence of `word` to the primary corpus.
+    pub fn learn(&mut self, word: &str) {
+        let word = word.trim().to_lowercase();
+        if let Some((corpus, _)) = self.corpora.first_mut() {
+            corpus.add(&word);
+        }
+        if let Some(bloom) = &mut self.bloom {
+            bloom.insert(&word);
+        }
+        if let Some(tree) = self.bk_tree.get_mut() {
+            tree.insert(&word);
+        }
+        if let Some(index) = &mut self.delete_index {
+            index.insert(&word);
+        }
+        if let Some(trie) = self.trie.get_mut() {
+            trie.insert(&word, 1);
+        }
+        if !self.accent_index.is_empty() {
+            let forms = self.accent_index.entry(fold_accents(&word)).or_default();
+            if !forms.contains(&word) {
+                forms.push(word);
+            }
+        }
+    }
+
+    // See `WordCounter::frequency_percentile`, in the primary corpus.
+    pub fn frequency_percentile(&self, word: &str) -> Option<f64> {
+        let (corpus, _) = self.corpora.first()?;
+        corpus.frequency_percentile(&word.to_lowercase())
+    }
+
+    // Lets `is_correct` reject most unknown words without corpus lookups.
+    pub fn with_bloom_filter(mut self, false_positive_rate: f64) -> Self {
+        let size = self.corpora.iter().map(|(corpus, _)| corpus.vocabulary_size()).sum();
+        let mut bloom = BloomFilter::new(size, false_positive_rate);
+        for (corpus, _) in &self.corpora {
+            for word in corpus.words() {
+                bloom.insert(word);
+            }
+        }
+        self.bloom = Some(bloom);
+        self
+    }
+
+    // Cleans `word` like the corpus text and learns what remains.
+    pub fn add_word(&mut self, word: &str) {
+        for cleaned in crate::word_counter::corpus_words(word) {
+            self.learn(&cleaned);
+        }
+    }
+
+    pub fn add_words<I>(&mut self, words: I)
+    where
+        I: IntoIterator<Item = String>
+    {
+        for word in words {
+            self.add_word(&word);
+        }
+    }
+
+    // Words match corpus words which differ from them only by accents,
+    // e.g. "cafe" is accepted and corrected to "café".
+    pub fn with_accent_insensitive(mut self, enabled: bool) -> Self {
+        self.accent_index.clear();
+        if enabled {
+            self.index_accents();
+        }
+        self
+    }
+
+    fn index_accents(&mut self) {
+        for (corpus, _) in &self.corpora {
+            for word in corpus.words() {
+                let forms = self.accent_index.entry(fold_accents(word)).or_default();
+                if !forms.contains(word) {
+                    forms.push(word.clone());
+                }
+            }
+        }
+    }
+
+    // Merges the corpora, the bigrams and the user dictionary of `other`
+    // into this checker and unions their alphabets, e.g. for a bilingual
+    // checker. The corpora are merged in order, so the primary corpora are
+    // merged together. Fails, leaving this checker as it is, when the options
+    // of the checkers differ or `other` has generators, which can't be copied.
+    // A Bloom filter takes the words of `other`, which raises its false positive rate.
+    pub fn merge(&mut self, other: &SpellChecker) -> Result<(), ConfigError> {
+        #[cfg(feature = "unicode-segmentation")]
+        let same_units = self.edits.graphemes == other.edits.graphemes;
+        #[cfg(not(feature = "unicode-segmentation"))]
+        let same_units = true;
+        let both_dicts = self.user_dict.total_count() > 0 && other.user_dict.total_count() > 0;
+        let conflicting = !same_units ||
+            self.edits.boundary_inserts_only != other.edits.boundary_inserts_only ||
+            self.max_len_diff != other.max_len_diff ||
+            self.max_rank_candidates != other.max_rank_candidates ||
+            self.alpha != other.alpha ||
+            self.error_model != other.error_model ||
+            self.smoothing != other.smoothing ||
+            self.keyboard != other.keyboard ||
+            self.bloom.is_some() != other.bloom.is_some() ||
+            (both_dicts && self.user_dict_boost != other.user_dict_boost) ||
+            !other.generators.is_empty();
+        if conflicting {
+            return Err(ConfigError::ConflictingOptions);
+        }
+
+        for (i, (corpus, weight)) in other.corpora.iter().enumerate() {
+            match self.corpora.get_mut(i) {
+                Some((own, _)) => own.merge(corpus),
+                None => self.corpora.push((corpus.clone(), *weight)),
+            }
+        }
+        let union = |own: &mut String, other: &str| {
+            for c in other.chars() {
+                if !own.contains(c) {
+                    own.push(c);
+                }
+            }
+        };
+        union(&mut self.alphabet, &other.alphabet);
+        union(&mut self.edits.insert_alphabet, &other.edits.insert_alphabet);
+        union(&mut self.edits.replace_alphabet, &other.edits.replace_alphabet);
+        self.bigrams.merge(&other.bigrams);
+        if self.user_dict.total_count() == 0 {
+            self.user_dict_boost = other.user_dict_boost;
+        }
+        self.user_dict.merge(&other.user_dict);
+
+        if let Some(bloom) = &mut self.bloom {
+            for (corpus, _) in &other.corpora {
+                for word in corpus.words() {
+                    bloom.insert(word);
+                }
+            }
+        }
+        if !self.accent_index.is_empty() || !other.accent_index.is_empty() {
+            self.index_accents();
+        }
+        if self.delete_index.is_some() || other.delete_index.is_some() {
+            self.index_deletes();
+        }
+        self.bk_tree = OnceLock::new();
+        self.trie = OnceLock::new();
+        Ok(())
+    }
+
+    // Corrects as if the case variants of each word were one word,
+    // returning the casing of the correction with the largest weighted
+    // count summed over the corpora.
+    pub fn correction_ci(&self, word: &str) -> String {
+        let mut folded = WordCounter::new();
+        let mut weighted: HashMap<String, f64> = HashMap::new();
+        let mut variants: HashMap<&String, f64> = HashMap::new();
+        for (corpus, weight) in &self.corpora {
+            for variant in corpus.words() {
+                let count = corpus.get(variant);
+                folded.add_count(variant, count);
+                *weighted.entry(variant.to_lowercase()).or_insert(0.0) += count as f64 * weight;
+                *variants.entry(variant).or_insert(0.0) += count as f64 * weight;
+            }
+        }
+        let mut casings: HashMap<String, (f64, &String)> = HashMap::new();
+        for (variant, score) in variants {
+            let casing = casings.entry(variant.to_lowercase()).or_insert((score, variant));
+            if score > casing.0 || (score == casing.0 && variant < casing.1) {
+                *casing = (score, variant);
+            }
+        }
+        let mut checker = SpellChecker::from_corpus(folded, &self.alphabet);
+        checker.edits = self.edits.clone();
+        checker.max_len_diff = self.max_len_diff;
+
+        let corrected = checker.candidates(&word.to_lowercase())
+            .into_iter()
+            .map(|candidate| (weighted.get(&candidate).copied().unwrap_or(0.0), candidate))
+            .min_by(|(a, x), (b, y)| b.partial_cmp(a).unwrap().then_with(|| x.cmp(y)))
+            .map(|(_, candidate)| candidate)
+            .expect("candidates returned empty range");
+        match casings.get(&corrected) {
+            Some((_, casing)) => casing.to_string(),
+            None => corrected,
+        }
+    }
+
+    // Like `candidates`, but scans the corpus words with Levenshtein automata
+    // instead of generating edits, which pays off for long words. Note that
+    // a transposition counts as two edits here.
+    pub fn scan_candidates(&self, word: &str) -> Vec<String> {
+        let automaton = LevenshteinAutomaton::new(word, 2);
+        let mut buckets: [Vec<String>; 3] = Default::default();
+        for (corpus, _) in &self.corpora {
+            for known in corpus.words() {
+                if let Some(distance) = automaton.distance(known) {
+                    buckets[distance].push(known.clone());
+                }
+            }
+        }
+        match buckets.iter_mut().find(|bucket| !bucket.is_empty()) {
+            Some(bucket) => {
+                bucket.sort_unstable();
+                bucket.dedup();
+                std::mem::take(bucket)
+            }
+            None => vec![word.to_owned()],
+        }
+    }
+
+    // Up to `n` corpus words starting with `prefix`, the most frequent first
+    // by their counts summed over the corpora. Ties are broken alphabetically.
+    pub fn completions(&self, prefix: &str, n: usize) -> Vec<String> {
+        let trie = self.trie.get_or_init(|| {
+            let mut trie = Trie::new();
+            for (corpus, _) in &self.corpora {
+                for word in corpus.words() {
+                    trie.insert(word, corpus.get(word));
+                }
+            }
+            trie
+        });
+        trie
+        .completions(&prefix.to_lowercase(), n)
+        .into_iter()
+        .map(|(word, _)| word)
+        .collect()
+    }
+
+    // Finds the nearest known words by probing an index of the words with up
+    // to two chars deleted, built now, instead of generating all edits of
+    // the input, for fast lookups at the cost of memory. Unlike the edits,
+    // the index also finds words with letters outside the alphabet. It is not
+    // used for accent-insensitive checkers.
+    pub fn with_symspell_index(mut self) -> Self {
+        self.index_deletes();
+        self
+    }
+
+    fn index_deletes(&mut self) {
+        let mut index = DeleteIndex::new(2);
+        for (corpus, _) in &self.corpora {
+            for word in corpus.words() {
+                index.insert(word);
+            }
+        }
+        for word in self.user_dict.words() {
+            index.insert(word);
+        }
+        self.delete_index = Some(index);
+    }
+
+    // Like `candidates`, but looks the nearest known words within two edits
+    // up in a BK-tree over the vocabulary instead of generating the edits,
+    // which pays off for long words. Unlike the edits, the tree also finds
+    // words with letters outside the alphabet. Accent-insensitive forms and
+    // the registered generators are not considered.
+    pub fn bk_candidates(&self, word: &str) -> Vec<String> {
+        let tree = self.bk_tree.get_or_init(|| {
+            let mut tree = BkTree::new();
+            for (corpus, _) in &self.corpora {
+                for known in corpus.words() {
+                    tree.insert(known);
+                }
+            }
+            for known in self.user_dict.words() {
+                tree.insert(known);
+            }
+            tree
+        });
+        let length = word.chars().count();
+        let matches = tree.query(word, 2)
+            .into_iter()
+            .filter(|(known, _)| known.chars().count().abs_diff(length) <= self.max_len_diff)
+            .collect::<Vec<(String, usize)>>();
+        match matches.first() {
+            Some(&(_, nearest)) => {
+                matches
+                .into_iter()
+                .take_while(|&(_, distance)| distance == nearest)
+                .map(|(known, _)| known)
+                .collect()
+            }
+            None => vec![word.to_owned()],
+        }
+    }
+
+    // Like `correction`, but with the candidates of `bk_candidates`.
+    pub fn correction_bk(&self, word: &str) -> String {
+        self.bk_candidates(word)
+        .into_iter()
+        .map(|candidate| (self.correction_score(word, &candidate), candidate))
+        .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+        .map(|(_, candidate)| candidate)
+        .expect("candidates returned empty range")
+    }
+
+    pub fn try_new_validated(corpus: &str, alphabet: &str) -> Result<Self, ConfigError> {
+        let checker = Self::new(corpus, alphabet);
+        checker.validate()?;
+        Ok(checker)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.corpora.iter().all(|(corpus, _)| corpus.total_count() == 0) {
+            return Err(ConfigError::EmptyCorpus);
+        }
+        let overlaps = self.corpora
+            .iter()
+            .flat_map(|(corpus, _)| corpus.words())
+            .any(|word| word.chars().any(|c| self.alphabet.contains(c)));
+        if overlaps { Ok(()) } else { Err(ConfigError::NoAlphabetOverlap) }
+    }
+}
+
+impl<C: Corpus> SpellChecker<C> {
+    pub fn from_corpus(corpus: C, alphabet: &str) -> Self {
+        Self::from_corpora(vec![(corpus, 1.0)], alphabet)
+    }
+
+    pub(crate) fn from_corpora(corpora: Vec<(C, f64)>, alphabet: &str) -> Self {
         SpellChecker {
-            corpus: WordCounter::from_str(corpus),
+            corpora,
             alphabet: alphabet.to_owned(),
+            edits: EditGenerator::new(alphabet),
+            bigrams: BigramCounter::new(),
+            user_dict: WordCounter::new(),
+            user_dict_boost: 0.0,
+            smoothing: false,
+            bloom: None,
+            max_len_diff: usize::MAX,
+            max_rank_candidates: usize::MAX,
+            keyboard: None,
+            alpha: 1.0,
+            error_model: None,
+            generators: Vec::new(),
+            accent_index: HashMap::new(),
+            bk_tree: OnceLock::new(),
+            delete_index: None,
+            trie: OnceLock::new(),
+        }
+    }
+
+    // The probability of a word is the weighted sum of
+    // its probabilities in each of the corpora.
+    pub fn blended(corpora: &[(C, f64)], alphabet: &str) -> Self
+    where
+        C: Clone
+    {
+        Self::from_corpora(corpora.to_vec(), alphabet)
+    }
+
+    pub fn with_bigrams(mut self, bigrams: BigramCounter) -> Self {
+        self.bigrams = bigrams;
+        self
+    }
+
+    // User dictionary words are accepted and their probability within the
+    // dictionary, times `boost`, is added to their corpus probability.
+    pub fn with_user_dict(mut self, user_dict: WordCounter, boost: f64) -> Self {
+        self.user_dict = user_dict;
+        self.user_dict_boost = boost;
+        self.bk_tree = OnceLock::new();
+        if let Some(index) = &mut self.delete_index {
+            for word in self.user_dict.words() {
+                index.insert(word);
+            }
         }
+        self
+    }
+
+    // With smoothing, the probability of a word in a corpus is
+    // (count + 1) / (total count + number of distinct words),
+    // so unseen words get a small positive probability.
+    pub fn with_smoothing(mut self, enabled: bool) -> Self {
+        self.smoothing = enabled;
+        self
+    }
+
+    pub fn with_keyboard_layout(mut self, layout: KeyboardLayout) -> Self {
+        self.keyboard = Some(layout);
+        self
+    }
+
+    pub fn with_generator<G>(mut self, generator: G) -> Self
+    where
+        G: CandidateGenerator + 'static
+    {
+        self.generators.push(Box::new(generator));
+        self
     }
 
+    // With `alpha` below 1.0 the candidates of `try_correction` are scored by
+    // alpha * normalized log probability + (1 - alpha) * similarity, where the
+    // log probability is scaled so that a single occurrence maps to 0.0 and
+    // the similarity is 1 - distance / length of the longer word. So alpha 1.0
+    // is the limit of the blend, which ranks by probability only.
+    // With an error model the known words of every distance up to two are
+    // ranked instead, and the probability of each candidate is multiplied by
+    // edit likelihood ^ distance, whether or not `alpha` is below 1.0.
     pub fn correction(&self, word: &str) -> String {
+        if self.alpha >= 1.0 && self.error_model.is_none() {
+            return self.try_correction(word).unwrap_or_else(|| word.to_owned());
+        }
+        let candidates = match self.error_model {
+            Some(_) => self.candidates_within_two(word),
+            None => {
+                self.candidates(word)
+                .into_iter()
+                .map(|candidate| {
+                    let distance = damerau_levenshtein(word, &candidate);
+                    (candidate, distance)
+                })
+                .collect()
+            }
+        };
+        let channel = |probability: f64, distance: usize| match self.error_model {
+            Some(likelihood) => probability * likelihood.powi(distance as i32),
+            None => probability,
+        };
+        if self.alpha >= 1.0 {
+            return self.best_candidate(word, candidates, |_, probability, distance| channel(probability, distance));
+        }
+        let log_min = (1.0 / self.total_count().max(1) as f64).ln();
+        let length = word.chars().count();
+        self.best_candidate(word, candidates, |candidate, probability, distance| {
+            let probability = channel(probability, distance);
+            let normalized = if log_min < 0.0 { 1.0 - probability.ln() / log_min } else { 1.0 };
+            let longer = length.max(candidate.chars().count()).max(1);
+            let similarity = 1.0 - distance as f64 / longer as f64;
+            self.alpha * normalized + (1.0 - self.alpha) * similarity
+        })
+    }
+
+    // None when no known correction of an unknown word was found within two edits.
+    // Only the first `max_rank_candidates` candidates, in alphabetical order,
+    // are scored, so with a cap the most probable candidate may be missed.
+    pub fn try_correction(&self, word: &str) -> Option<String> {
+        let (correction, _probability) = self.scored_candidates(word)
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .expect("candidates returned empty range");
+        if correction == word && !self.is_known(word) {
+            return None;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            word,
+            correction = correction.as_str(),
+            probability = _probability,
+            "chose correction"
+        );
+        Some(correction)
+    }
+
+    // Replaces only words with known candidates exactly one edit away.
+    pub fn correction_conservative(&self, word: &str) -> String {
+        match self.nearest_known(word) {
+            Some((candidates, 1)) => {
+                candidates
+                .into_iter()
+                .map(|candidate| (self.correction_score(word, &candidate), candidate))
+                .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+                .map(|(_, candidate)| candidate)
+                .expect("candidates returned empty range")
+            }
+            _ => word.to_owned(),
+        }
+    }
+
+    // The candidates ranked by `try_correction`, in alphabetical order.
+    pub(crate) fn scored_candidates(&self, word: &str) -> Vec<(String, f64)> {
+        self.candidates(word)
+        .into_iter()
+        .take(self.max_rank_candidates)
+        .map(|candidate| {
+            let score = self.correction_score(word, &candidate);
+            (candidate, score)
+        })
+        .collect()
+    }
+
+    fn correction_score(&self, word: &str, candidate: &str) -> f64 {
+        let probability = self.probability(candidate);
+        match &self.keyboard {
+            Some(layout) if layout.is_adjacent_replace(&word.to_lowercase(), candidate) => {
+                probability * ADJACENT_KEY_BONUS
+            }
+            _ => probability,
+        }
+    }
+
+    // Picks the candidate within edit distance 2 which maximizes
+    // `score_fn(candidate, probability, distance)`, where the probability is
+    // the one `try_correction` ranks by. The candidates come from the same
+    // sources and options as in `try_correction`, but all of them are
+    // ranked and not only the nearest ones.
+    pub fn correction_by<F>(&self, word: &str, score_fn: F) -> String
+    where
+        F: Fn(&str, f64, usize) -> f64
+    {
+        self.best_candidate(word, self.candidates_within_two(word), score_fn)
+    }
+
+    // The first `max_rank_candidates` of `candidates` maximizing
+    // `score_fn(candidate, probability, distance)`, or `word` without candidates.
+    fn best_candidate<F>(&self, word: &str, candidates: Vec<(String, usize)>, score_fn: F) -> String
+    where
+        F: Fn(&str, f64, usize) -> f64
+    {
+        candidates
+        .into_iter()
+        .take(self.max_rank_candidates)
+        .map(|(candidate, distance)| {
+            let score = score_fn(&candidate, self.correction_score(word, &candidate), distance);
+            (candidate, score)
+        })
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(candidate, _)| candidate)
+        .unwrap_or_else(|| word.to_owned())
+    }
+
+    // The allowed word nearest to `word`, within two edits, even if it
+    // is not in the corpus. Ties are broken by probability and then
+    // alphabetically. None when no allowed word is close enough.
+    pub fn correction_to_set(&self, word: &str, allowed: &HashSet<String>) -> Option<String> {
+        allowed
+        .iter()
+        .map(|candidate| (damerau_levenshtein(word, candidate), self.probability(candidate), candidate))
+        .filter(|&(distance, _, _)| distance <= 2)
+        .min_by(|a, b| {
+            a.0.cmp(&b.0)
+            .then(b.1.partial_cmp(&a.1).unwrap())
+            .then_with(|| a.2.cmp(b.2))
+        })
+        .map(|(_, _, candidate)| candidate.clone())
+    }
+
+    pub fn correction_or_mark<F>(&self, word: &str, mark_fn: F) -> String
+    where
+        F: Fn(&str) -> String
+    {
+        self.try_correction(word).unwrap_or_else(|| mark_fn(word))
+    }
+
+    // Like `correction`, but the candidates are scored by
+    // CONTEXT_WEIGHT * P(candidate | prev) + (1 - CONTEXT_WEIGHT) * P(candidate),
+    // where P(candidate | prev) comes from the corpus bigrams. Without bigrams
+    // starting with `prev`, this is the same as ranking by probability.
+    pub fn correct_in_context(&self, prev: &str, word: &str) -> String {
+        let prev = prev.trim().to_lowercase();
+        let following = self.bigrams.following_count(&prev);
         self.candidates(word)
         .into_iter()
-        .max_by(|a, b| self.probability(a).partial_cmp(&self.probability(b)).unwrap())
+        .map(|candidate| {
+            let conditional = if following > 0 {
+                self.bigrams.get(&prev, &candidate) as f64 / following as f64
+            }
+            else { 0.0 };
+            let score = CONTEXT_WEIGHT * conditional + (1.0 - CONTEXT_WEIGHT) * self.probability(&candidate);
+            (score, candidate)
+        })
+        .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+        .map(|(_, candidate)| candidate)
+        .expect("candidates returned empty range")
+    }
+
+    // Candidates are ordered by probability (descending), edit distance,
+    // length in chars and finally lexicographically, so ties are always
+    // broken the same way.
+    pub fn correction_deterministic(&self, word: &str) -> String {
+        let (candidates, distance) = self.nearest_candidates(word);
+        candidates
+        .into_iter()
+        .map(|c| (self.probability(&c), distance, c.chars().count(), c))
+        .min_by(|a, b| {
+            b.0.partial_cmp(&a.0).unwrap()
+            .then(a.1.cmp(&b.1))
+            .then(a.2.cmp(&b.2))
+            .then_with(|| a.3.cmp(&b.3))
+        })
+        .map(|(_, _, _, c)| c)
         .expect("candidates returned empty range")
     }
 
-    pub fn probability(&self, word: &str) -> f64 {
-        if self.corpus.total_count() > 0 {
-            self.corpus.get(word) as f64 / self.corpus.total_count() as f64
+    // How many of the sample inputs are corrected to `word`.
+    pub fn attractiveness(&self, word: &str, sample: &[&str]) -> usize {
+        sample
+        .iter()
+        .filter(|input| self.correction(input) == word)
+        .count()
+    }
+
+    // Corrects the lowercased word and gives the correction the
+    // capitalization of `word`: all-lower, Titlecase or ALL-CAPS.
+    pub fn correction_preserving_case(&self, word: &str) -> String {
+        Casing::of(word).apply(&self.correction(&word.to_lowercase()))
+    }
+
+    pub fn correction_lower(&self, word: &str) -> String {
+        self.correction(word).to_lowercase()
+    }
+
+    #[cfg(feature = "futures")]
+    pub fn correct_stream<'a, S>(&'a self, words: S) -> impl futures::Stream<Item = String> + 'a
+    where
+        S: futures::Stream<Item = String> + 'a
+    {
+        use futures::StreamExt;
+        words.map(move |word| self.correction(&word))
+    }
+
+    pub fn correct_text(&self, text: &str) -> String {
+        self.correct_tokens(text, |word| self.is_correct(word))
+    }
+
+    // Like `correct_text`, but writes the corrected text to `out` a line
+    // at a time, so only a single line is held in memory.
+    pub fn correct_text_to<W: Write>(&self, text: &str, mut out: W) -> io::Result<()> {
+        for line in text.split_inclusive('\n') {
+            out.write_all(self.correct_text(line).as_bytes())?;
+        }
+        out.flush()
+    }
+
+    // Collapses the whitespace within each line to single spaces
+    // and corrects the words. Line breaks are kept.
+    pub fn normalize_and_correct(&self, text: &str) -> String {
+        text
+        .lines()
+        .map(|line| {
+            let line = line.split_whitespace().collect::<Vec<&str>>().join(" ");
+            self.correct_text(&line)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+    }
+
+    // Words which occur at least `min_occurrences` times in the text
+    // are treated as correct, even if the corpus doesn't know them.
+    pub fn correct_text_with_local_vocabulary(&self, text: &str, min_occurrences: u32) -> String {
+        let mut local = WordCounter::new();
+        for token in tokenizer::tokenize(text) {
+            local.add(token.text);
+        }
+        self.correct_tokens(text, |word| {
+            self.is_correct(word) || local.get(&word.to_lowercase()) >= min_occurrences
+        })
+    }
+
+    // The suggestions are ranked by a blend of the corpus probabilities and
+    // the probabilities among the correct words of the text, so the words
+    // of the topic of the text are preferred.
+    pub fn check_text(&self, text: &str) -> Vec<Misspelling> {
+        self.check_with_local_vocabulary(text, &self.local_vocabulary(text))
+    }
+
+    // The correct words of the text.
+    fn local_vocabulary(&self, text: &str) -> WordCounter {
+        let mut local = WordCounter::new();
+        for token in tokenizer::tokenize(text) {
+            if self.is_correct(token.text) {
+                local.add(token.text);
+            }
+        }
+        local
+    }
+
+    fn check_with_local_vocabulary(&self, text: &str, local: &WordCounter) -> Vec<Misspelling> {
+        tokenizer::tokenize(text)
+        .into_iter()
+        .filter(|token| !self.is_correct(token.text))
+        .map(|token| Misspelling {
+            word: token.text.to_owned(),
+            line: token.line,
+            column: token.column,
+            start: token.start,
+            end: token.end(),
+            suggestions: self.local_suggestions(&token.text.to_lowercase(), local),
+        })
+        .collect()
+    }
+
+    // Checks the lines of the text in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn check_text_par(&self, text: &str) -> Vec<Misspelling>
+    where
+        C: Sync
+    {
+        use rayon::prelude::*;
+        let mut offset = 0;
+        let lines = text
+            .split('\n')
+            .map(|line| {
+                let start = offset;
+                offset += line.len() + 1;
+                (start, line)
+            })
+            .collect::<Vec<(usize, &str)>>();
+        let local = lines
+            .par_iter()
+            .map(|(_, line)| self.local_vocabulary(line))
+            .reduce(WordCounter::new, |mut local, other| {
+                local.merge(&other);
+                local
+            });
+        lines
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(i, &(offset, line))| {
+            self.check_with_local_vocabulary(line, &local)
+            .into_iter()
+            .map(move |misspelling| Misspelling {
+                line: i,
+                start: misspelling.start + offset,
+                end: misspelling.end + offset,
+                ..misspelling
+            })
+        })
+        .collect()
+    }
+
+    // The most probable candidates for an unknown word.
+    fn suggestions_for(&self, word: &str) -> Vec<String> {
+        self.ranked_candidates(word)
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(candidate, _)| candidate)
+        .collect()
+    }
+
+    // Like `suggestions_for`, but blends in the probabilities within `local`.
+    fn local_suggestions(&self, word: &str, local: &WordCounter) -> Vec<String> {
+        if local.total_count() == 0 {
+            return self.suggestions_for(word);
+        }
+        let mut candidates = self.ranked_candidates(word)
+            .into_iter()
+            .map(|(candidate, p)| {
+                let blended = (1.0 - LOCAL_WEIGHT) * p + LOCAL_WEIGHT * local.probability(&candidate);
+                (candidate, blended)
+            })
+            .collect::<Vec<(String, f64)>>();
+        candidates.sort_by(|(a, p), (b, q)| q.partial_cmp(p).unwrap().then_with(|| a.cmp(b)));
+        candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(candidate, _)| candidate)
+        .collect()
+    }
+
+    // Up to `n` of the ranked candidates of `word`. A word without
+    // known candidates is suggested as is, with probability 0.0.
+    pub fn suggestions(&self, word: &str, n: usize) -> Vec<(String, f64)> {
+        let mut ranked = self.ranked_candidates(word);
+        if ranked.is_empty() {
+            ranked.push((word.to_owned(), 0.0));
+        }
+        ranked.truncate(n);
+        ranked
+    }
+
+    // Up to `n` known words within two edits of `word` other than `word` itself,
+    // even if it is known. Nearer words come first, then more probable ones.
+    pub fn suggestions_even_if_correct(&self, word: &str, n: usize) -> Vec<(String, f64)> {
+        let mut neighbors = self.words_within(word, 2)
+            .into_iter()
+            .filter(|(neighbor, _)| neighbor != word)
+            .map(|(neighbor, distance)| {
+                let p = self.probability(&neighbor);
+                (neighbor, distance, p)
+            })
+            .collect::<Vec<(String, usize, f64)>>();
+        neighbors.sort_by(|(a, d, p), (b, e, q)| {
+            d.cmp(e)
+            .then(q.partial_cmp(p).unwrap())
+            .then_with(|| a.cmp(b))
+        });
+        neighbors
+        .into_iter()
+        .take(n)
+        .map(|(neighbor, _, p)| (neighbor, p))
+        .collect()
+    }
+
+    // The known candidates with their probabilities, the most probable first.
+    // Ties are broken alphabetically.
+    pub(crate) fn ranked_candidates(&self, word: &str) -> Vec<(String, f64)> {
+        let mut candidates = self.candidates(word)
+            .into_iter()
+            .filter(|candidate| self.is_known(candidate))
+            .map(|candidate| {
+                let p = self.probability(&candidate);
+                (candidate, p)
+            })
+            .collect::<Vec<(String, f64)>>();
+        candidates.sort_by(|(a, p), (b, q)| q.partial_cmp(p).unwrap().then_with(|| a.cmp(b)));
+        candidates
+    }
+
+    // The probability of the best candidate divided by the sum of the
+    // probabilities of all candidates. A word without known candidates
+    // has confidence 0.0 and a single known candidate has confidence 1.0.
+    pub fn correction_confidence(&self, word: &str) -> f64 {
+        let probabilities = self.candidates(word)
+            .iter()
+            .map(|candidate| self.probability(candidate))
+            .collect::<Vec<f64>>();
+        let sum = probabilities.iter().sum::<f64>();
+        if sum <= 0.0 {
+            return 0.0;
+        }
+        let best = probabilities.iter().cloned().fold(0.0, f64::max);
+        (best / sum).clamp(0.0, 1.0)
+    }
+
+    // The best correction of `word` other than `word` itself, even if it is known,
+    // e.g. "there" for "their". Only the nearest other known words are ranked.
+    pub fn alternative_correction(&self, word: &str) -> Option<String> {
+        let length = word.chars().count();
+        let others = |edits: HashSet<String>| {
+            edits
+            .iter()
+            .flat_map(|edit| self.known_forms(edit))
+            .filter(|known| known != word && known.chars().count().abs_diff(length) <= self.max_len_diff)
+            .collect::<Vec<String>>()
+        };
+        let mut candidates = others(self.edits1(word));
+        if candidates.is_empty() {
+            candidates = others(self.edits2_only(word));
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+        .into_iter()
+        .map(|candidate| (self.correction_score(word, &candidate), candidate))
+        .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+        .map(|(_, candidate)| candidate)
+    }
+
+    // The probability of the best candidate minus that of the second best,
+    // or of the best alone if it is the only one. 0.0 without known candidates.
+    pub fn correction_margin(&self, word: &str) -> f64 {
+        match self.ranked_candidates(word).as_slice() {
+            [] => 0.0,
+            [(_, best)] => *best,
+            [(_, best), (_, second), ..] => best - second,
+        }
+    }
+
+    // The best candidate other than the correction, if there are at least two.
+    pub fn second_best(&self, word: &str) -> Option<String> {
+        let ranked = self.ranked_candidates(word);
+        if ranked.len() < 2 {
+            return None;
+        }
+        let best = self.correction(word);
+        ranked
+        .into_iter()
+        .map(|(candidate, _)| candidate)
+        .find(|candidate| *candidate != best)
+    }
+
+    // The known words within two edits of `word` with their posterior
+    // probabilities, most probable first. A noisy channel model is used:
+    // P(candidate | word) is proportional to P(candidate) * P(word | candidate),
+    // where the error model P(word | candidate) is the edit likelihood to the power
+    // of the edit distance. Unless set, each edit makes a typo 100 times less likely.
+    pub fn correction_posterior(&self, word: &str) -> Vec<(String, f64)> {
+        let likelihood = self.error_model.unwrap_or(EDIT_LIKELIHOOD);
+        let mut scored = self.words_within(word, 2)
+            .into_iter()
+            .map(|(candidate, distance)| {
+                let score = self.probability(&candidate) * likelihood.powi(distance as i32);
+                (candidate, score)
+            })
+            .collect::<Vec<(String, f64)>>();
+        let total = scored.iter().map(|(_, score)| score).sum::<f64>();
+        if total == 0.0 {
+            return Vec::new();
+        }
+        for (_, score) in &mut scored {
+            *score /= total;
+        }
+        scored.sort_by(|(a, x), (b, y)| y.partial_cmp(x).unwrap().then_with(|| a.cmp(b)));
+        scored
+    }
+
+    // Known words one edit away from `word`: first the most probable one
+    // for each kind of edit, then the rest by probability.
+    pub fn diverse_suggestions(&self, word: &str, n: usize) -> Vec<String> {
+        let by_probability = |words: &mut Vec<String>| {
+            words.sort_by(|a, b| {
+                self.probability(b).partial_cmp(&self.probability(a)).unwrap().then_with(|| a.cmp(b))
+            });
+        };
+        let mut picks: Vec<String> = Vec::new();
+        let mut all = Vec::new();
+        for (_, edits) in self.edits1_by_operation(word) {
+            let mut known = edits
+                .into_iter()
+                .filter(|edit| edit != word && self.is_known(edit))
+                .collect::<Vec<String>>();
+            by_probability(&mut known);
+            if let Some(best) = known.iter().find(|edit| !picks.contains(edit)) {
+                picks.push(best.clone());
+            }
+            all.extend(known);
+        }
+        by_probability(&mut picks);
+        by_probability(&mut all);
+        all.dedup();
+        picks
+        .iter()
+        .cloned()
+        .chain(all.into_iter().filter(|edit| !picks.contains(edit)))
+        .take(n)
+        .collect()
+    }
+
+    // The fraction of distinct words in the text that are known.
+    // A text without words is fully covered.
+    pub fn vocabulary_coverage(&self, text: &str) -> f64 {
+        let words = tokenizer::tokenize(text)
+            .iter()
+            .map(|token| token.text.to_lowercase())
+            .collect::<HashSet<String>>();
+        if words.is_empty() {
+            return 1.0;
+        }
+        let known = words.iter().filter(|word| self.is_known(word)).count();
+        known as f64 / words.len() as f64
+    }
+
+    // The distinct words of the text, sorted, which are neither correct
+    // nor correctable, i.e. what the corpus lacks to spell the text.
+    pub fn missing_vocabulary(&self, text: &str) -> Vec<String> {
+        let mut missing = tokenizer::tokenize(text)
+            .iter()
+            .map(|token| token.text.to_lowercase())
+            .filter(|word| !self.is_correct(word) && self.try_correction(word).is_none())
+            .collect::<Vec<String>>();
+        missing.sort_unstable();
+        missing.dedup();
+        missing
+    }
+
+    // The number of known words one edit away from `word`, not counting itself.
+    pub fn neighborhood_density(&self, word: &str) -> usize {
+        self.known(&self.edits1(word))
+        .iter()
+        .filter(|&&known| known != word)
+        .count()
+    }
+
+    // The fraction of (misspelling, correct) pairs whose correct word is known
+    // and one edit away from the misspelling, 0.0 for no pairs.
+    pub fn edits1_recall(&self, test_pairs: &[(&str, &str)]) -> f64 {
+        if test_pairs.is_empty() {
+            return 0.0;
+        }
+        let reachable = test_pairs
+            .iter()
+            .filter(|(misspelling, correct)| {
+                self.known(&self.edits1(misspelling))
+                .iter()
+                .any(|known| known == correct)
+            })
+            .count();
+        reachable as f64 / test_pairs.len() as f64
+    }
+
+    // Reports the second word of each pair of equal words
+    // separated only by whitespace, along with its line and column.
+    pub fn detect_repeated_words(&self, text: &str) -> Vec<(String, usize, usize)> {
+        tokenizer::tokenize(text)
+        .windows(2)
+        .filter(|pair| {
+            let (first, second) = (pair[0], pair[1]);
+            first.text.to_lowercase() == second.text.to_lowercase() &&
+            text[first.end()..second.start].chars().all(char::is_whitespace)
+        })
+        .map(|pair| (pair[1].text.to_owned(), pair[1].line, pair[1].column))
+        .collect()
+    }
+
+    // Reports adjacent words separated only by whitespace whose
+    // swapped order is far more frequent in the corpus bigrams.
+    pub fn detect_word_transpositions(&self, text: &str) -> Vec<WordSwap> {
+        tokenizer::tokenize(text)
+        .windows(2)
+        .filter(|pair| text[pair[0].end()..pair[1].start].chars().all(char::is_whitespace))
+        .filter_map(|pair| {
+            let first = pair[0].text.to_lowercase();
+            let second = pair[1].text.to_lowercase();
+            let written = self.bigrams.get(&first, &second) as f64;
+            let swapped = self.bigrams.get(&second, &first) as f64;
+            if swapped + 1.0 >= SWAP_RATIO * (written + 1.0) {
+                Some(WordSwap {
+                    first: pair[0].text.to_owned(),
+                    second: pair[1].text.to_owned(),
+                    line: pair[0].line,
+                    column: pair[0].column,
+                })
+            }
+            else { None }
+        })
+        .collect()
+    }
+
+    // Reports known words for which a known word one edit away is far more
+    // likely next to the surrounding words, e.g. "too" in "went too the store".
+    pub fn check_real_words(&self, text: &str) -> Vec<Misspelling> {
+        self.check_real_words_with_ratio(text, REAL_WORD_RATIO)
+    }
+
+    // Like `check_real_words`, but an alternative must be more than `min_ratio`
+    // times as likely in context as the written word. A ratio below 1.0 counts
+    // as 1.0, so a word more likely than its alternatives is never reported.
+    // In context, a word scores P(word | previous) * P(next | word), with
+    // add-one smoothed bigram counts, from the neighbors separated from it
+    // only by whitespace. Words without such neighbors are not checked.
+    pub fn check_real_words_with_ratio(&self, text: &str, min_ratio: f64) -> Vec<Misspelling> {
+        let min_ratio = min_ratio.max(1.0);
+        let vocabulary = self.corpora.iter().map(|(corpus, _)| corpus.vocabulary_size()).sum::<usize>();
+        let tokens = tokenizer::tokenize(text);
+        let adjacent = |i: usize| {
+            let (first, second) = (&tokens[i], &tokens[i + 1]);
+            text[first.end()..second.start].chars().all(char::is_whitespace)
+        };
+
+        let mut result = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            let word = token.text.to_lowercase();
+            let prev = (i > 0 && adjacent(i - 1)).then(|| tokens[i - 1].text.to_lowercase());
+            let next = (i + 1 < tokens.len() && adjacent(i)).then(|| tokens[i + 1].text.to_lowercase());
+            if (prev.is_none() && next.is_none()) || !self.is_known(&word) {
+                continue;
+            }
+            let score = |candidate: &str| {
+                let before = prev.as_ref().map_or(1.0, |prev| self.bigrams.get(prev, candidate) as f64 + 1.0);
+                let after = next.as_ref().map_or(1.0, |next| {
+                    (self.bigrams.get(candidate, next) as f64 + 1.0) /
+                    (self.bigrams.following_count(candidate) as usize + vocabulary) as f64
+                });
+                before * after
+            };
+            let own = score(&word);
+            let mut alternatives = self.edits1(&word)
+                .iter()
+                .flat_map(|edit| self.known_forms(edit))
+                .filter(|alternative| *alternative != word)
+                .collect::<Vec<String>>();
+            alternatives.sort_unstable();
+            alternatives.dedup();
+            let mut likelier = alternatives
+                .into_iter()
+                .map(|alternative| (score(&alternative), alternative))
+                .filter(|(alternative_score, _)| *alternative_score > min_ratio * own)
+                .collect::<Vec<(f64, String)>>();
+            if likelier.is_empty() {
+                continue;
+            }
+            likelier.sort_by(|(x, a), (y, b)| y.partial_cmp(x).unwrap().then_with(|| a.cmp(b)));
+            result.push(Misspelling {
+                word: token.text.to_owned(),
+                line: token.line,
+                column: token.column,
+                start: token.start,
+                end: token.end(),
+                suggestions: likelier
+                    .into_iter()
+                    .take(MAX_SUGGESTIONS)
+                    .map(|(_, alternative)| alternative)
+                    .collect(),
+            });
+        }
+        result
+    }
+
+    fn correct_tokens<F>(&self, text: &str, is_valid: F) -> String
+    where
+        F: Fn(&str) -> bool
+    {
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+        for token in tokenizer::tokenize(text) {
+            result.push_str(&text[last..token.start]);
+            if is_valid(token.text) {
+                result.push_str(token.text);
+            }
+            else {
+                result.push_str(&self.correct_token(token.text));
+            }
+            last = token.end();
+        }
+        result.push_str(&text[last..]);
+        result
+    }
+
+    // Tokens which are known or can't be corrected are kept as they are,
+    // corrections get the capitalization of the token.
+    fn correct_token(&self, token: &str) -> String {
+        let word = token.to_lowercase();
+        if self.is_known(&word) {
+            return token.to_owned();
+        }
+        let correction = self.correction(&word);
+        if correction == word { token.to_owned() } else { Casing::of(token).apply(&correction) }
+    }
+
+    pub fn correction_pairs(&self, text: &str) -> Vec<(String, String)> {
+        tokenizer::tokenize(text)
+        .into_iter()
+        .map(|token| (token.text.to_owned(), self.correct_token(token.text)))
+        .collect()
+    }
+
+    pub fn probability(&self, word: &str) -> f64 {
+        let p = self.corpora
+            .iter()
+            .map(|(corpus, weight)| {
+                if self.smoothing {
+                    let total = corpus.total_count() as f64 + corpus.vocabulary_size() as f64;
+                    weight * (corpus.get(word) as f64 + 1.0) / total.max(1.0)
+                }
+                else {
+                    weight * corpus.probability(word)
+                }
+            })
+            .sum::<f64>();
+        p + self.user_dict_boost * self.user_dict.probability(word)
+    }
+
+    // The raw count and total, summed over all corpora regardless of their weights.
+    pub fn frequency(&self, word: &str) -> (u32, u32) {
+        self.corpora
+        .iter()
+        .fold((0, 0), |(count, total), (corpus, _)| {
+            (count + corpus.get(word), total + corpus.total_count())
+        })
+    }
+
+    fn total_count(&self) -> u32 {
+        self.corpora.iter().map(|(corpus, _)| corpus.total_count()).sum()
+    }
+
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        if let Some(bloom) = &self.bloom {
+            let only_corpus = self.accent_index.is_empty() && self.user_dict.get(&word) == 0;
+            if only_corpus && !bloom.might_contain(&word) {
+                return false;
+            }
+        }
+        !self.known_forms(&word).is_empty()
+    }
+
+    // The corpus words `word` stands for.
+    fn known_forms(&self, word: &str) -> Vec<String> {
+        let mut forms = Vec::new();
+        if self.is_known(word) {
+            forms.push(word.to_owned());
+        }
+        if !self.accent_index.is_empty() {
+            if let Some(originals) = self.accent_index.get(&fold_accents(word)) {
+                forms.extend(originals.iter().filter(|&o| o != word).cloned());
+            }
+        }
+        forms
+    }
+
+    fn is_known(&self, word: &str) -> bool {
+        self.user_dict.get(word) > 0 ||
+        self.corpora.iter().any(|(corpus, _)| corpus.get(word) > 0)
+    }
+
+    // The most probable split of `text` into words, using the corpus
+    // probabilities and a penalty for unknown words which grows with their length.
+    // Known words are never split and unknown words without known parts are kept whole.
+    pub fn segment(&self, text: &str) -> Vec<String> {
+        let text = text.to_lowercase();
+        if self.is_known(&text) {
+            return vec![text];
+        }
+        let bounds = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()))
+            .collect::<Vec<usize>>();
+        let total = self.total_count();
+        let mut best: Vec<(f64, usize)> = vec![(0.0, 0)];
+        for end in 1..bounds.len() {
+            let candidate = (0..end)
+                .map(|start| {
+                    let word = &text[bounds[start]..bounds[end]];
+                    (best[start].0 + self.segment_log_probability(word, total), start)
+                })
+                .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+                .unwrap();
+            best.push(candidate);
+        }
+
+        // Adjacent unknown parts are joined, as they are one unknown word.
+        let mut words: Vec<String> = Vec::new();
+        let mut end = bounds.len() - 1;
+        while end > 0 {
+            let start = best[end].1;
+            let part = &text[bounds[start]..bounds[end]];
+            match words.last_mut() {
+                Some(last) if !self.is_known(part) && !self.is_known(last) => last.insert_str(0, part),
+                _ => words.push(part.to_owned()),
+            }
+            end = start;
+        }
+        words.reverse();
+        words
+    }
+
+    // The most probable split of a compound into known words, if any.
+    // Unlike `segment`, unknown parts are not allowed.
+    pub fn decompose(&self, word: &str) -> Option<Vec<String>> {
+        let word = word.to_lowercase();
+        let bounds = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(word.len()))
+            .collect::<Vec<usize>>();
+        let mut best: Vec<Option<(f64, usize)>> = vec![Some((0.0, 0))];
+        for end in 1..bounds.len() {
+            let candidate = (0..end)
+                .filter_map(|start| {
+                    let (score, _) = best[start]?;
+                    let p = self.probability(&word[bounds[start]..bounds[end]]);
+                    if p > 0.0 { Some((score + p.log10(), start)) } else { None }
+                })
+                .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+            best.push(candidate);
+        }
+
+        let mut parts = Vec::new();
+        let mut end = bounds.len() - 1;
+        while end > 0 {
+            let (_, start) = best[end]?;
+            parts.push(word[bounds[start]..bounds[end]].to_owned());
+            end = start;
+        }
+        parts.reverse();
+        if parts.is_empty() { None } else { Some(parts) }
+    }
+
+    fn segment_log_probability(&self, word: &str, total: u32) -> f64 {
+        let p = self.probability(word);
+        if p > 0.0 {
+            p.log10()
+        }
+        else {
+            (10.0 / total.max(1) as f64).log10() - word.chars().count() as f64
+        }
+    }
+
+    // The nearest known words, merged with the candidates of the registered generators.
+    pub fn candidates(&self, word: &str) -> Vec<String> {
+        if self.generators.is_empty() {
+            return self.nearest_candidates(word).0;
+        }
+        let mut candidates = match self.nearest_known(word) {
+            Some((words, 0)) => return words,
+            Some((words, _)) => words,
+            None => Vec::new(),
+        };
+        for generator in &self.generators {
+            candidates.extend(generator.candidates(word, self));
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        if candidates.is_empty() {
+            candidates.push(word.to_owned());
+        }
+        candidates
+    }
+
+    // Like `candidates`, but checks the corpus after each kind of single edit:
+    // deletes, transposes, replaces and then inserts, and stops at the first
+    // kind yielding known words. So a subset of the nearest candidates may be
+    // returned, for less work on the common typos fixed by a delete.
+    pub fn candidates_progressive(&self, word: &str) -> Vec<String> {
+        let known_forms = |edits: Vec<String>| {
+            let mut words = edits
+                .iter()
+                .flat_map(|edit| self.known_forms(edit))
+                .collect::<Vec<String>>();
+            words.sort_unstable();
+            words.dedup();
+            words
+        };
+        let known = known_forms(vec![word.to_owned()]);
+        if !known.is_empty() {
+            return known;
+        }
+        let splits = self.edits.unit_splits(word);
+        let kinds: [&dyn Fn() -> Vec<String>; 4] = [
+            &|| self.edits.single_deletes(&splits),
+            &|| self.edits.adjacent_transposes(&splits),
+            &|| self.edits.single_replaces(&splits),
+            &|| self.edits.single_inserts(&splits),
+        ];
+        for generate in kinds.iter() {
+            let known = known_forms(generate())
+                .into_iter()
+                .filter(|known| known.chars().count().abs_diff(word.chars().count()) <= self.max_len_diff)
+                .collect::<Vec<String>>();
+            if !known.is_empty() {
+                return known;
+            }
+        }
+        self.nearest_candidates(word).0
+    }
+
+    // Like `candidates`, but the known words of every distance up to two are
+    // returned, paired with their distance, in alphabetical order. The words
+    // from the registered generators are paired with their edit distance.
+    // Known words are their only candidates.
+    fn candidates_within_two(&self, word: &str) -> Vec<(String, usize)> {
+        let length = word.chars().count();
+        let mut nearest: HashMap<String, usize> = HashMap::new();
+        match &self.delete_index {
+            Some(index) if self.accent_index.is_empty() => {
+                for (known, distance) in index.lookup(word) {
+                    nearest.entry(known).or_insert(distance);
+                }
+            }
+            _ => {
+                let tiers: [(Box<dyn Iterator<Item = String>>, usize); 3] = [
+                    (Box::new(std::iter::once(word.to_owned())), 0),
+                    (Box::new(self.edits.edits1_iter(word)), 1),
+                    (Box::new(self.edits2_only(word).into_iter()), 2),
+                ];
+                for (edits, distance) in tiers {
+                    for known in edits.flat_map(|edit| self.known_forms(&edit)) {
+                        nearest.entry(known).or_insert(distance);
+                    }
+                }
+            }
+        }
+        nearest.retain(|known, _| known.chars().count().abs_diff(length) <= self.max_len_diff);
+        if nearest.values().any(|&distance| distance == 0) {
+            nearest.retain(|_, distance| *distance == 0);
+        }
+        else {
+            for generator in &self.generators {
+                for candidate in generator.candidates(word, self) {
+                    let distance = damerau_levenshtein(word, &candidate);
+                    nearest.entry(candidate).or_insert(distance);
+                }
+            }
+        }
+        let mut candidates = nearest.into_iter().collect::<Vec<(String, usize)>>();
+        candidates.sort_unstable();
+        candidates
+    }
+
+    // The candidates along with the number of edits they are away from `word`.
+    fn nearest_candidates(&self, word: &str) -> (Vec<String>, usize) {
+        self.nearest_known(word).unwrap_or_else(|| (vec![word.to_owned()], 0))
+    }
+
+    fn nearest_known(&self, word: &str) -> Option<(Vec<String>, usize)> {
+        let length = word.chars().count();
+        let known_words = |edits: &mut dyn Iterator<Item = String>, distance| {
+            let words = edits
+                .flat_map(|edit| self.known_forms(&edit))
+                .filter(|known| known.chars().count().abs_diff(length) <= self.max_len_diff)
+                .collect::<HashSet<String>>();
+            if !words.is_empty() {
+                let mut vec = words.into_iter().collect::<Vec<String>>();
+                vec.sort_unstable();
+                Some((vec, distance))
+            }
+            else { None }
+        };
+        
+        let result = match &self.delete_index {
+            Some(index) if self.accent_index.is_empty() => {
+                let matches = index.lookup(word)
+                    .into_iter()
+                    .filter(|(known, _)| known.chars().count().abs_diff(length) <= self.max_len_diff)
+                    .collect::<Vec<(String, usize)>>();
+                matches.first().map(|&(_, nearest)| {
+                    let words = matches
+                        .iter()
+                        .take_while(|&&(_, distance)| distance == nearest)
+                        .map(|(known, _)| known.clone())
+                        .collect();
+                    (words, nearest)
+                })
+            }
+            _ => {
+                known_words(&mut std::iter::once(word.to_owned()), 0)
+                .or_else(|| known_words(&mut self.edits.edits1_iter(word), 1))
+                .or_else(|| known_words(&mut self.edits2_only(word).into_iter(), 2))
+            }
+        };
+        #[cfg(feature = "tracing")]
+        match &result {
+            Some((words, distance)) => {
+                tracing::debug!(word, distance, candidates = words.len(), "found known candidates")
+            }
+            None => tracing::debug!(word, "no known candidates within two edits"),
+        }
+        result
+    }
+    
+    // Returns the known words within `max_distance` edits of `word`
+    // paired with their distance, sorted by distance and then alphabetically.
+    pub fn words_within(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        let mut seen = HashSet::new();
+        seen.insert(word.to_owned());
+        let mut frontier = seen.clone();
+        let mut result = Vec::new();
+        for distance in 0..=max_distance {
+            if distance > 0 {
+                frontier = frontier
+                    .iter()
+                    .flat_map(|w| self.edits1(w))
+                    .filter(|w| seen.insert(w.clone()))
+                    .collect();
+            }
+            let mut words = self.known(&frontier)
+                .into_iter()
+                .map(|w| (w.to_owned(), distance))
+                .collect::<Vec<(String, usize)>>();
+            words.sort_unstable();
+            result.extend(words);
+        }
+        result
+    }
+
+    // Lazily yields the known words within `max_distance` edits of `word`,
+    // in no particular order. Only the edits of the words on the current
+    // path are kept in memory, instead of all edits at each distance.
+    pub fn known_within_iter(&self, word: &str, max_distance: usize) -> impl Iterator<Item = String> + '_ {
+        let mut yielded = HashSet::new();
+        let mut stack = vec![(word.to_owned(), 0)];
+        std::iter::from_fn(move || {
+            while let Some((candidate, distance)) = stack.pop() {
+                if distance < max_distance {
+                    stack.extend(self.edits1(&candidate).into_iter().map(|edit| (edit, distance + 1)));
+                }
+                if self.is_known(&candidate) && yielded.insert(candidate.clone()) {
+                    return Some(candidate);
+                }
+            }
+            None
+        })
+    }
+
+    // A shortest sequence of known words, each one edit away
+    // from the previous, leading from `from` to `to`.
+    pub fn edit_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        if !self.is_known(from) || !self.is_known(to) {
+            return None;
+        }
+        let mut previous: HashMap<String, String> = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(from.to_owned());
+        previous.insert(from.to_owned(), String::new());
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![current];
+                while path.last().unwrap() != from {
+                    let before = previous[path.last().unwrap()].clone();
+                    path.push(before);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let edits = self.edits1(&current);
+            for next in self.known(&edits) {
+                if !previous.contains_key(next) {
+                    previous.insert(next.clone(), current.clone());
+                    queue.push_back(next.clone());
+                }
+            }
+        }
+        None
+    }
+
+    pub fn known<'a>(&self, words: &'a HashSet<String>) -> Vec<&'a String> {
+        words
+        .iter()
+        .filter(|word| self.is_known(word))
+        .collect()
+    }
+
+    pub fn edits1(&self, word: &str) -> HashSet<String> {
+        self.edits.edits1(word)
+    }
+
+    pub fn edits1_iter<'a>(&'a self, word: &'a str) -> impl Iterator<Item = String> + 'a {
+        self.edits.edits1_iter(word)
+    }
+
+    pub fn edits1_by_operation(&self, word: &str) -> Vec<(EditOperation, Vec<String>)> {
+        self.edits.edits1_by_operation(word)
+    }
+
+    pub fn edits2(&self, word: &str) -> HashSet<String> {
+        self.edits.edits2(word)
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn edits2_parallel(&self, word: &str) -> HashSet<String> {
+        self.edits.edits2_parallel(word)
+    }
+
+    // The words which are exactly two edits away from `word`,
+    // generated in parallel with the `parallel` feature.
+    pub fn edits2_only(&self, word: &str) -> HashSet<String> {
+        self.edits.edits2_only(word)
+    }
+}
+
+// A checker counts the words of its corpora and its user dictionary together, unweighted.
+impl<C: Corpus> Corpus for SpellChecker<C> {
+    fn get(&self, word: &str) -> u32 {
+        self.frequency(word).0 + self.user_dict.get(word)
+    }
+
+    fn total_count(&self) -> u32 {
+        SpellChecker::total_count(self) + self.user_dict.total_count()
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        self.corpora.iter().map(|(corpus, _)| corpus.vocabulary_size()).sum::<usize>() +
+        self.user_dict.vocabulary_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits1_with_empty_alphabet() {
+        let checker = SpellChecker::new("", "");
+        let word = "ab";
+        let expected_words = as_set(&["a", "b", "ba"]);
+        
+        assert_eq!(checker.edits1(word), expected_words);
+    }
+    
+    #[test]
+    fn edits1_with_nonempty_en_alphabet() {
+        let checker = SpellChecker::new("", "c");
+        let word = "ab";
+        let expected_words = as_set(&["cb", "b", "acb", "abc", "ba", "a", "cab", "ac"]);
+
+        assert_eq!(checker.edits1(word), expected_words); 
+    }
+    
+    #[test]
+    fn edits1_with_nonempty_bg_alphabet() {
+        let checker = SpellChecker::new("", "з");
+        let word = "ей";
+        let expected_words = as_set(&["ез", "езй", "й", "зй", "ейз", "зей", "е", "йе"]);
+
+        assert_eq!(checker.edits1(word), expected_words); 
+    }
+
+    #[test]
+    fn edits2_with_empty_alphabet() {
+        let checker = SpellChecker::new("", "");
+        let word = "ab";
+        let expected_words = as_set(&["", "ab", "a", "b"]);
+
+        assert_eq!(checker.edits2(word), expected_words);
+    }
+
+    #[test]
+    fn edits2_with_nonempty_en_alphabet() {
+        let checker = SpellChecker::new("", "c");
+        let word = "ab";
+        let expected_words = as_set(&[
+            "", "a", "cb", "cac", "bc", "cba", "acbc", "cab", "ac",
+            "acc", "abcc", "ab", "c", "accb", "cbc", "ca", "cc", "cacb",
+            "ccb", "acb", "abc", "cabc", "bca", "ccab", "b", "bac",
+        ]);
+        
+        assert_eq!(checker.edits2(word), expected_words);
+    }
+
+    #[test]
+    fn edits2_with_nonempty_bg_alphabet() {
+        let checker = SpellChecker::new("", "з");
+        let word = "ей";
+        let expected_words = as_set(&[
+            "", "зез", "езйз", "ейз", "з", "зей", "зз", "зйе", "ез",
+            "езй", "йез", "зейз", "ейзз", "еззй", "ззй", "зй", "зе",
+            "йзе", "зезй", "е", "ззей", "ей", "йз", "езз", "й", "зйз",
+        ]);
+        
+        assert_eq!(checker.edits2(word), expected_words);
+    }
+
+    #[test]
+    fn known_words_with_empty_corpus() {
+        let checker = SpellChecker::new("", ALPHABET_EN);
+        let words = as_set(&["a", "b"]);
+
+        let known_words = checker.known(&words);
+        
+        assert!(known_words.is_empty());
+    }
+
+    #[test]
+    fn known_words_with_nonempty_corpus_and_words_which_are_not_in_the_corpus() {
+        let checker = SpellChecker::new("one two three изненада", ALPHABET_EN);
+        let words = as_set(&["a", "й"]);
+
+        let known_words = checker.known(&words);
+        
+        assert!(known_words.is_empty());
+    }
+    
+    #[test]
+    fn known_words_with_nonempty_corpus_and_words_which_are_in_the_corpus() {
+        let checker = SpellChecker::new("one two three изненада", ALPHABET_EN);
+        let words = as_set(&["a", "b", "изненада"]);
+        let expected_word = "изненада".to_owned();
+
+        let known_words = checker.known(&words);
+        
+        assert_eq!(known_words.len(), 1);
+        assert!(known_words.contains(&&expected_word));
+    }
+
+    fn as_set(words: &[&str]) -> HashSet<String> {
+        words.iter().map(|&s| s.to_owned()).collect()
+    }
+
+    #[test]
+    fn candidates_with_very_different_word_from_ones_on_corpus() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let word = "hamlet".to_owned();
+
+        let candidates = checker.candidates(&word);
+
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates.contains(&word));
+    }
+    
+    #[test]
+    fn candidates_with_one_letter_difference() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let word = "ide".to_owned();
+        let expected = "ice".to_owned();
+
+        let candidates = checker.candidates(&word);
+
+        assert_eq!(candidates.len(), 1);
+        assert!(candidates.contains(&expected));
+    }
+    
+    #[test]
+    fn candidates_with_two_letters_difference() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let word = "idde".to_owned();
+        let expected = ["dice", "ice", "isle"];
+
+        let candidates = checker.candidates(&word);
+
+        assert_eq!(candidates, expected);
+    }
+
+    #[test]
+    fn correction_with_very_different_word_from_ones_on_corpus() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let word = "hamlet".to_owned();
+
+        let correction = checker.correction(&word);
+
+        assert_eq!(correction, word);
+    }
+
+    #[test]
+    fn correction_with_one_letter_difference() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let word = "ide".to_owned();
+        let expected = "ice".to_owned();
+
+        let correction = checker.correction(&word);
+
+        assert_eq!(correction, expected);
+    }
+
+    #[test]
+    fn correction_with_two_letters_difference() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let word = "idde";
+        let expected = "isle";
+
+        let correction = checker.correction(word);
+
+        assert_eq!(correction, expected);
+    }
+
+    #[test]
+    fn is_correct_ignores_case() {
+        let checker = SpellChecker::new("one two three", ALPHABET_EN);
+
+        assert!(checker.is_correct("Two"));
+        assert!(!checker.is_correct("four"));
+    }
+
+    #[test]
+    fn correct_text_keeps_the_layout() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+        let text = "the  cta, sat\non a mtt!";
+
+        let corrected = checker.correct_text(text);
+
+        assert_eq!(corrected, "the  cat, sat\non a mat!");
+    }
+
+    #[test]
+    fn correct_text_with_local_vocabulary_accepts_repeated_words() {
+        let checker = SpellChecker::new("the rodeo was loud", ALPHABET_EN);
+        let text = "frodo was loud. the frodo, frodo";
+
+        assert_eq!(checker.correct_text(text), "rodeo was loud. the rodeo, rodeo");
+        assert_eq!(checker.correct_text_with_local_vocabulary(text, 3), text);
+        assert_eq!(
+            checker.correct_text_with_local_vocabulary(text, 4),
+            "rodeo was loud. the rodeo, rodeo"
+        );
+    }
+
+    #[test]
+    fn correction_lower_lowercases_uncorrectable_words() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        assert_eq!(checker.correction_lower("XYZ"), "xyz");
+        assert_eq!(checker.correction_lower("ide"), "ice");
+    }
+
+    #[test]
+    fn blended_probability_is_weighted_sum() {
+        let en = WordCounter::from_str("cat dog dog dog");
+        let other = WordCounter::from_str("cat cot");
+        let checker = SpellChecker::blended(&[(en, 0.7), (other, 0.3)], ALPHABET_EN);
+
+        assert!((checker.probability("cat") - (0.7 * 0.25 + 0.3 * 0.5)).abs() < 1e-9);
+        assert!((checker.probability("cot") - 0.15).abs() < 1e-9);
+        assert_eq!(checker.probability("cow"), 0.0);
+    }
+
+    #[test]
+    fn blended_correction_with_word_only_in_minority_corpus() {
+        let en = WordCounter::from_str("cat dog dog dog dog dog dog dog dog dog");
+        let other = WordCounter::from_str("cot");
+        let checker = SpellChecker::blended(&[(en, 0.7), (other, 0.3)], ALPHABET_EN);
+
+        assert!(checker.is_correct("cot"));
+        assert_eq!(checker.correction("cst"), "cot");
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn correct_stream_keeps_the_order() {
+        use futures::{executor, stream, StreamExt};
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let words = stream::iter(vec!["ide".to_owned(), "mce".to_owned(), "hamlet".to_owned()]);
+
+        let corrected = executor::block_on(checker.correct_stream(words).collect::<Vec<String>>());
+
+        assert_eq!(corrected, ["ice", "mice", "hamlet"]);
+    }
+
+    #[test]
+    fn detect_repeated_words_ignores_case() {
+        let checker = SpellChecker::new("", ALPHABET_EN);
+
+        assert_eq!(checker.detect_repeated_words("the the cat"), [("the".to_owned(), 0, 4)]);
+        assert_eq!(checker.detect_repeated_words("a cat\nThe\n the"), [("the".to_owned(), 2, 1)]);
+    }
+
+    #[test]
+    fn detect_repeated_words_skips_words_separated_by_punctuation() {
+        let checker = SpellChecker::new("", ALPHABET_EN);
+
+        assert!(checker.detect_repeated_words("no, no, the cat").is_empty());
+    }
+
+    #[test]
+    fn words_within_reports_distances() {
+        let checker = SpellChecker::new("cat cost cot dog", ALPHABET_EN);
+
+        let words = checker.words_within("cot", 2);
+
+        assert_eq!(words, [
+            ("cot".to_owned(), 0),
+            ("cat".to_owned(), 1),
+            ("cost".to_owned(), 1),
+            ("dog".to_owned(), 2),
+        ]);
+    }
+
+    #[test]
+    fn correction_by_custom_score() {
+        let checker = SpellChecker::new("cat cost cost cost", ALPHABET_EN);
+        let word = "cxt";
+
+        assert_eq!(checker.correction(word), "cat");
+        assert_eq!(checker.correction_by(word, |_, p, _| p), "cost");
+        assert_eq!(checker.correction_by(word, |_, p, d| p - d as f64), "cat");
+        assert_eq!(checker.correction_by("hamlet", |_, p, _| p), "hamlet");
+    }
+
+    #[test]
+    fn try_new_validated_with_empty_corpus() {
+        let result = SpellChecker::try_new_validated(" \n ", ALPHABET_EN);
+
+        assert_eq!(result.err(), Some(ConfigError::EmptyCorpus));
+    }
+
+    #[test]
+    fn try_new_validated_with_mismatched_alphabet() {
+        let result = SpellChecker::try_new_validated("one two three", ALPHABET_BG);
+
+        assert_eq!(result.err(), Some(ConfigError::NoAlphabetOverlap));
+    }
+
+    #[test]
+    fn try_new_validated_with_valid_configuration() {
+        let checker = SpellChecker::try_new_validated("едно две one", ALPHABET_BG).unwrap();
+
+        assert_eq!(checker.correction("едн"), "едно");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_preserves_corrections() {
+        let en = WordCounter::from_str("ice isle spie crie dice mice mic");
+        let bg = WordCounter::from_str("лед мед");
+        let checker = SpellChecker::blended(&[(en, 0.6), (bg, 0.4)], ALPHABET_EN);
+
+        let json = serde_json::to_string(&checker).unwrap();
+        let restored: SpellChecker = serde_json::from_str(&json).unwrap();
+
+        for word in ["ide", "idde", "hamlet", "лед", "mce"].iter() {
+            assert_eq!(restored.correction(word), checker.correction(word));
+            assert_eq!(restored.probability(word), checker.probability(word));
+        }
+    }
+
+    #[test]
+    fn edits2_only_has_no_overlap_with_edits1() {
+        let checker = SpellChecker::new("", ALPHABET_EN);
+        let word = "idde";
+
+        let edits1 = checker.edits1(word);
+        let edits2_only = checker.edits2_only(word);
+
+        assert!(edits2_only.is_disjoint(&edits1));
+        assert!(!edits2_only.contains(word));
+        let mut all = edits1.union(&edits2_only).cloned().collect::<HashSet<String>>();
+        all.insert(word.to_owned());
+        assert_eq!(all, checker.edits2(word));
+    }
+
+    #[test]
+    fn correction_or_mark_marks_only_uncorrectable_words() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let mark = |word: &str| format!("«{}»", word);
+
+        assert_eq!(checker.correction_or_mark("hamlet", mark), "«hamlet»");
+        assert_eq!(checker.correction_or_mark("ide", mark), "ice");
+        assert_eq!(checker.correction_or_mark("mice", mark), "mice");
+    }
+
+    #[test]
+    fn correction_deterministic_breaks_ties_by_length_and_lexicographically() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        for _ in 0..3 {
+            assert_eq!(checker.correction_deterministic("idde"), "ice");
+        }
+        assert_eq!(checker.correction_deterministic("mie"), "mic");
+        assert_eq!(checker.correction_deterministic("hamlet"), "hamlet");
+    }
+
+    #[test]
+    fn with_number_words_corrects_number_words_missing_from_the_corpus() {
+        let checker = SpellChecker::new("a cat sat on a mat", ALPHABET_EN);
+        assert_eq!(checker.correction("thre"), "thre");
+
+        let checker = checker.with_number_words(Language::English);
+
+        assert!(checker.is_correct("eleven"));
+        assert_eq!(checker.correction("thre"), "three");
+        assert_eq!(checker.correction("caat"), "cat");
+    }
+
+    #[test]
+    fn with_number_words_updates_the_bloom_filter_and_the_accent_index() {
+        let checker = SpellChecker::new("a cat sat on a mat", ALPHABET_EN)
+            .with_bloom_filter(0.01)
+            .with_accent_insensitive(true)
+            .with_number_words(Language::English);
+
+        assert!(checker.is_correct("eleven"));
+        assert!(checker.is_correct("Three"));
+        assert_eq!(checker.correction("thre"), "three");
+        assert!(checker.accent_index.contains_key("eleven"));
+    }
+
+    #[test]
+    fn with_number_words_in_bulgarian() {
+        let checker = SpellChecker::new("", ALPHABET_BG).with_number_words(Language::Bulgarian);
+
+        assert_eq!(checker.correction("четри"), "четири");
+    }
+
+    #[test]
+    fn edit_path_between_known_words() {
+        let checker = SpellChecker::new("cold cord card ward warm worm", ALPHABET_EN);
+
+        let path = checker.edit_path("cold", "warm");
+
+        assert_eq!(path.unwrap(), ["cold", "cord", "card", "ward", "warm"]);
+        assert_eq!(checker.edit_path("cold", "cold").unwrap(), ["cold"]);
+    }
+
+    #[test]
+    fn edit_path_without_a_ladder() {
+        let checker = SpellChecker::new("cold cord warm", ALPHABET_EN);
+
+        assert_eq!(checker.edit_path("cold", "warm"), None);
+        assert_eq!(checker.edit_path("cold", "hot"), None);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn correction_emits_tracing_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        struct Fields(Vec<String>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0.push(format!("{}={:?}", field.name(), value));
+            }
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.push(format!("{}={}", field.name(), value));
+            }
+        }
+
+        struct Collector(Arc<Mutex<Vec<String>>>);
+
+        impl tracing::Subscriber for Collector {
+            fn enabled(&self, _: &Metadata) -> bool { true }
+            fn new_span(&self, _: &Attributes) -> Id { Id::from_u64(1) }
+            fn record(&self, _: &Id, _: &Record) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event) {
+                let mut fields = Fields(Vec::new());
+                event.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0.join(" "));
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        let correction = tracing::subscriber::with_default(Collector(events.clone()), || {
+            checker.correction("ide")
+        });
+
+        let events = events.lock().unwrap();
+        assert_eq!(correction, "ice");
+        assert!(events.iter().any(|e| e.contains("distance=1") && e.contains("candidates=1")));
+        assert!(events.iter().any(|e| e.contains("correction=ice")));
+    }
+
+    #[test]
+    fn vocabulary_coverage_counts_distinct_words() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+
+        assert_eq!(checker.vocabulary_coverage("The cat, the dog, the cat and the bird."), 0.4);
+        assert_eq!(checker.vocabulary_coverage("the cat sat"), 1.0);
+        assert_eq!(checker.vocabulary_coverage(" ... "), 1.0);
+    }
+
+    #[test]
+    fn check_text_reports_unknown_words() {
+        let checker = SpellChecker::new("the cat sat on a mat mat", ALPHABET_EN);
+
+        let misspellings = checker.check_text("The cst sat\non a xat, hamlet");
+
+        assert_eq!(misspellings, [
+            Misspelling { word: "cst".to_owned(), line: 0, column: 4, start: 4, end: 7, suggestions: vec!["cat".to_owned()] },
+            Misspelling {
+                word: "xat".to_owned(),
+                line: 1,
+                column: 5,
+                start: 17,
+                end: 20,
+                suggestions: vec!["sat".to_owned(), "mat".to_owned(), "cat".to_owned()],
+            },
+            Misspelling { word: "hamlet".to_owned(), line: 1, column: 10, start: 22, end: 28, suggestions: vec![] },
+        ]);
+    }
+
+    #[test]
+    fn check_text_reports_byte_offsets_after_multibyte_words() {
+        let checker = SpellChecker::new("къща куче", ALPHABET_BG);
+        let text = "Къща, кучу\nкуче къшa";
+
+        let misspellings = checker.check_text(text);
+
+        let spans = misspellings.iter().map(|m| &text[m.start..m.end]).collect::<Vec<&str>>();
+        assert_eq!(spans, ["кучу", "къшa"]);
+        assert_eq!((misspellings[0].start, misspellings[0].column), (10, 6));
+        assert_eq!(misspellings[0].suggestions, ["куче"]);
+    }
+
+    #[test]
+    fn frequency_sums_raw_counts_of_all_corpora() {
+        let checker = SpellChecker::new("ice dice ice", ALPHABET_EN);
+        assert_eq!(checker.frequency("ice"), (2, 3));
+
+        let first = WordCounter::from_str("ice dice ice");
+        let second = WordCounter::from_str("ice mice");
+        let checker = SpellChecker::blended(&[(first, 0.9), (second, 0.1)], ALPHABET_EN);
+        assert_eq!(checker.frequency("ice"), (3, 5));
+        assert_eq!(checker.frequency("mic"), (0, 5));
+    }
+
+    #[test]
+    fn edits1_by_operation_covers_edits1() {
+        let checker = SpellChecker::new("", "c");
+
+        let edits = checker.edits1_by_operation("ab");
+
+        assert_eq!(edits[0], (EditOperation::Delete, vec!["b".to_owned(), "a".to_owned()]));
+        assert_eq!(edits[1], (EditOperation::Transpose, vec!["ba".to_owned()]));
+        let all = edits.into_iter().flat_map(|(_, words)| words).collect::<HashSet<String>>();
+        assert_eq!(all, checker.edits1("ab"));
+    }
+
+    #[test]
+    fn diverse_suggestions_span_several_operations() {
+        let corpus = "cat cat cat cat cat hat hat hat hat mat mat mat at bats abt";
+        let checker = SpellChecker::new(corpus, ALPHABET_EN);
+
+        assert_eq!(checker.suggestions_for("bat")[..3], ["cat", "hat", "mat"]);
+        assert_eq!(checker.diverse_suggestions("bat", 3), ["cat", "abt", "at"]);
+        assert_eq!(checker.diverse_suggestions("bat", 5), ["cat", "abt", "at", "bats", "hat"]);
+        assert!(checker.diverse_suggestions("hamlet", 3).is_empty());
+    }
+
+    #[test]
+    fn segment_spaceless_text() {
+        let checker = SpellChecker::new("the best day ever is a day to be at the sea", ALPHABET_EN);
+
+        assert_eq!(checker.segment("bestdayever"), ["best", "day", "ever"]);
+        assert_eq!(checker.segment("BestDayEverxyz"), ["best", "day", "ever", "xyz"]);
+        assert!(checker.segment("").is_empty());
+    }
+
+    #[test]
+    fn segment_keeps_known_and_unsplittable_words() {
+        let checker = SpellChecker::new("hello hello hello world world world helloworld", ALPHABET_EN);
+
+        assert_eq!(checker.segment("helloworld"), ["helloworld"]);
+        assert_eq!(SpellChecker::new("hello world", ALPHABET_EN).segment("helloworld"), ["hello", "world"]);
+        assert_eq!(checker.segment("xyzzy"), ["xyzzy"]);
+        assert_eq!(checker.segment("World"), ["world"]);
+    }
+
+    #[test]
+    fn correction_pairs_keep_the_token_order() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+
+        let pairs = checker.correction_pairs("The cat sta on a mat.");
+
+        let expected = [("The", "The"), ("cat", "cat"), ("sta", "sat"), ("on", "on"), ("a", "a"), ("mat", "mat")];
+        let expected = expected
+            .iter()
+            .map(|&(a, b)| (a.to_owned(), b.to_owned()))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn separate_insert_alphabet() {
+        let checker = SpellChecker::builder("", ALPHABET_EN).insert_alphabet("aeiou").build();
+        let default = SpellChecker::new("", ALPHABET_EN);
+        let splits = crate::generator::splits("ct");
+
+        let inserts = checker.edits.single_inserts(&splits);
+
+        assert_eq!(inserts.len(), 3 * 5);
+        assert!(inserts.contains(&"cat".to_owned()));
+        assert!(!inserts.contains(&"cxt".to_owned()));
+        assert_eq!(default.edits.single_inserts(&splits).len(), 3 * 26);
+        assert_eq!(checker.edits.single_replaces(&splits), default.edits.single_replaces(&splits));
+    }
+
+    #[test]
+    fn attractiveness_of_common_and_rare_words() {
+        let checker = SpellChecker::new("the the the the then they tea", ALPHABET_EN);
+        let sample = ["teh", "th", "thw", "hte", "tha", "thr", "tez"];
+
+        assert_eq!(checker.attractiveness("the", &sample), 6);
+        assert_eq!(checker.attractiveness("tea", &sample), 1);
+        assert_eq!(checker.attractiveness("then", &sample), 0);
+    }
+
+    #[test]
+    fn accent_insensitive_matching() {
+        let checker = SpellChecker::new("café naïve tea", ALPHABET_EN);
+        assert!(!checker.is_correct("cafe"));
+
+        let checker = checker.with_accent_insensitive(true);
+
+        assert!(checker.is_correct("cafe"));
+        assert!(checker.is_correct("Café"));
+        assert_eq!(checker.correction("cafe"), "café");
+        assert_eq!(checker.correction("cafw"), "café");
+        assert_eq!(checker.correction("naive"), "naïve");
+        assert!(!checker.with_accent_insensitive(false).is_correct("cafe"));
+    }
+
+    #[test]
+    fn second_best_is_distinct_from_correction() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        let second = checker.second_best("idde").unwrap();
+
+        assert_ne!(second, checker.correction("idde"));
+        assert!(checker.candidates("idde").contains(&second));
+    }
+
+    #[test]
+    fn second_best_prefers_the_more_probable_runner_up() {
+        let checker = SpellChecker::new("bat bat bat cat cat hat", ALPHABET_EN);
+
+        assert_eq!(checker.correction("xat"), "bat");
+        assert_eq!(checker.second_best("xat").unwrap(), "cat");
+        assert_eq!(checker.second_best("bat"), None);
+        assert_eq!(checker.second_best("hamlet"), None);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn check_text_par_matches_check_text() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+        let text = "the cst sat\n\non a xat, hamlet\r\n  tha mat\nsta";
+
+        assert_eq!(checker.check_text_par(text), checker.check_text(text));
+        assert_eq!(checker.check_text_par(text).len(), 5);
+    }
+
+    #[test]
+    fn correction_confidence_of_ambiguous_and_unambiguous_words() {
+        let checker = SpellChecker::new("bat bat bat cat hat ice", ALPHABET_EN);
+
+        assert!((checker.correction_confidence("xat") - 0.6).abs() < 1e-9);
+        assert_eq!(checker.correction_confidence("ic"), 1.0);
+        assert_eq!(checker.correction_confidence("hamlet"), 0.0);
+        assert!(checker.correction_confidence("xat") < checker.correction_confidence("ic"));
+    }
+
+    #[test]
+    fn normalize_and_correct_messy_text() {
+        let checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+        let text = "  the   cst \t sat  \n\n on  a\tmqt.   ";
+
+        let normalized = checker.normalize_and_correct(text);
+
+        assert_eq!(normalized, "the cat sat\n\non a mat.");
+    }
+
+    struct Abbreviations;
+
+    impl CandidateGenerator for Abbreviations {
+        fn candidates(&self, word: &str, corpus: &dyn Corpus) -> Vec<String> {
+            match word {
+                "thx" if corpus.get("thanks") > 0 => vec!["thanks".to_owned()],
+                _ => vec![],
+            }
+        }
+    }
+
+    #[test]
+    fn custom_candidate_generators_are_ranked_with_edits() {
+        let checker = SpellChecker::new("thanks thanks tax", ALPHABET_EN);
+        assert_eq!(checker.correction("thx"), "tax");
+
+        let checker = checker.with_generator(Abbreviations);
+
+        assert_eq!(checker.candidates("thx"), ["tax", "thanks"]);
+        assert_eq!(checker.correction("thx"), "thanks");
+        assert_eq!(checker.candidates("hamlet"), ["hamlet"]);
+    }
+
+    struct Expansions;
+
+    impl CandidateGenerator for Expansions {
+        fn candidates(&self, word: &str, corpus: &dyn Corpus) -> Vec<String> {
+            ["thanks", "brb"]
+            .iter()
+            .filter(|expansion| corpus.get(expansion) > 0 && **expansion != word)
+            .map(|expansion| expansion.to_string())
+            .collect()
+        }
+    }
+
+    #[test]
+    fn custom_candidate_generators_keep_known_words() {
+        let mut dict = WordCounter::new();
+        dict.add("brb");
+        let checker = SpellChecker::new("thanks thanks thanks tax", ALPHABET_EN)
+            .with_user_dict(dict, 1.0)
+            .with_generator(Expansions);
+
+        assert_eq!(checker.candidates("tax"), ["tax"]);
+        assert_eq!(checker.correction("tax"), "tax");
+        assert_eq!(checker.candidates("txa"), ["brb", "tax", "thanks"]);
+        assert_eq!(Corpus::get(&checker, "brb"), 1);
+        assert_eq!(Corpus::total_count(&checker), 5);
+        assert_eq!(Corpus::probability(&checker, "brb"), 0.2);
+    }
+
+    #[test]
+    fn known_words_with_several_accent_forms_are_sorted() {
+        let checker = SpellChecker::new("cafe café cafè", "abcdefghijklmnopqrstuvwxyzéè").with_accent_insensitive(true);
+
+        for _ in 0..10 {
+            assert_eq!(checker.candidates_within_two("cafe"), [
+                ("cafe".to_owned(), 0),
+                ("cafè".to_owned(), 0),
+                ("café".to_owned(), 0),
+            ]);
+        }
+    }
+
+    #[test]
+    fn correction_by_ranks_the_candidates_of_try_correction() {
+        let checker = SpellChecker::builder("thanks thanks tax cat", ALPHABET_EN)
+            .max_len_diff(0)
+            .alpha(0.5)
+            .build()
+            .with_generator(Abbreviations);
+
+        assert_eq!(checker.correction("caat"), "caat");
+        assert_eq!(checker.correction("thx"), "thanks");
+        assert_eq!(checker.correction_by("thx", |_, _, distance| -(distance as f64)), "tax");
+
+        let accents = SpellChecker::builder("café", "abcdefghijklmnopqrstuvwxyzé").alpha(0.5).build()
+            .with_accent_insensitive(true);
+        assert_eq!(accents.correction("cafe"), "café");
+        let indexed = SpellChecker::builder("spelling", ALPHABET_EN).alpha(0.5).build().with_symspell_index();
+        assert_eq!(indexed.correction("speling"), "spelling");
+    }
+
+    #[test]
+    fn correction_ci_returns_the_dominant_casing() {
+        let corpus = WordCounter::from_str_case_sensitive("The The The the THE ten");
+        let checker = SpellChecker::from_corpus(corpus, ALPHABET_EN);
+
+        assert_eq!(checker.correction_ci("teh"), "The");
+        assert_eq!(checker.correction_ci("TEN"), "ten");
+        assert_eq!(checker.correction_ci("xyzzy"), "xyzzy");
+    }
+
+    #[test]
+    fn correction_ci_keeps_fractional_weighted_counts() {
+        let checker = SpellChecker::blended(&[
+            (WordCounter::from_str_case_sensitive("Cat"), 0.45),
+            (WordCounter::from_str_case_sensitive("cut cut cut"), 0.1),
+        ], ALPHABET_EN);
+
+        assert_eq!(checker.correction_ci("cxt"), "Cat");
+        assert_eq!(checker.correction_ci("cuts"), "cut");
+    }
+
+    #[test]
+    fn correction_ci_sums_the_casings_over_the_corpora() {
+        let checker = SpellChecker::blended(&[
+            (WordCounter::from_str_case_sensitive("The"), 0.4),
+            (WordCounter::from_str_case_sensitive("The"), 0.4),
+            (WordCounter::from_str_case_sensitive("THE THE"), 0.3),
+        ], ALPHABET_EN);
+
+        assert_eq!(checker.correction_ci("teh"), "The");
+    }
+
+    #[test]
+    fn detect_word_transpositions() {
+        let corpus = "the cat sat on the mat\n".repeat(10);
+        let checker = SpellChecker::new(&corpus, ALPHABET_EN);
+
+        let swaps = checker.detect_word_transpositions("The mat\ncat the sat");
+
+        assert_eq!(swaps, [WordSwap {
+            first: "cat".to_owned(),
+            second: "the".to_owned(),
+            line: 1,
+            column: 0,
+        }]);
+        assert!(checker.detect_word_transpositions("cat, the").is_empty());
+    }
+
+    struct CountingCorpus {
+        counter: WordCounter,
+        probability_calls: std::cell::Cell<usize>,
+    }
+
+    impl Corpus for CountingCorpus {
+        fn get(&self, word: &str) -> u32 {
+            self.counter.get(word)
         }
-        else {
-            0.0
+
+        fn total_count(&self) -> u32 {
+            self.counter.total_count()
+        }
+
+        fn probability(&self, word: &str) -> f64 {
+            self.probability_calls.set(self.probability_calls.get() + 1);
+            self.get(word) as f64 / self.total_count() as f64
         }
     }
 
-    pub fn candidates(&self, word: &str) -> Vec<String> {
-        let known_words = |edits| {
-            let words = self.known(&edits);
-            if !words.is_empty() {
-                let mut vec = words.iter()
-                    .map(|&s| s.to_owned())
-                    .collect::<Vec<String>>();
-                vec.sort_unstable_by(|a, b| a.cmp(b));
-                Some(vec)
-            }
-            else { None }
+    #[test]
+    fn max_rank_candidates_caps_probability_calls() {
+        let corpus = CountingCorpus {
+            counter: WordCounter::from_str("bat cat eat fat fat hat"),
+            probability_calls: std::cell::Cell::new(0),
         };
-        
-        let edits = [word].iter().map(|s| s.to_string()).collect();
-        known_words(edits)
-        .or_else(|| known_words(self.edits1(word)))
-        .or_else(|| known_words(self.edits2(word)))
-        .unwrap_or_else(|| vec![word.to_owned()])
+        let mut checker = SpellChecker::from_corpus(corpus, ALPHABET_EN);
+        checker.max_rank_candidates = 2;
+
+        assert_eq!(checker.candidates("xat").len(), 5);
+        assert_eq!(checker.correction("xat"), "cat");
+        assert_eq!(checker.corpora[0].0.probability_calls.get(), 2);
     }
-    
-    pub fn known<'a>(&self, words: &'a HashSet<String>) -> Vec<&'a String> {
-        words
-        .iter()
-        .filter(|word| self.corpus.get(word) > 0)
-        .collect()
+
+    #[test]
+    fn known_within_iter_matches_words_within() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        for (word, distance) in [("ide", 1), ("ide", 2), ("mic", 0), ("xyz", 2)].iter() {
+            let eager = checker.words_within(word, *distance)
+                .into_iter()
+                .map(|(w, _)| w)
+                .collect::<HashSet<String>>();
+            let lazy = checker.known_within_iter(word, *distance).collect::<Vec<String>>();
+
+            assert_eq!(lazy.len(), eager.len());
+            assert_eq!(lazy.into_iter().collect::<HashSet<String>>(), eager);
+        }
+        assert_eq!(checker.known_within_iter("ide", 2).take(1).count(), 1);
     }
 
-    pub fn edits1(&self, word: &str) -> HashSet<String> {
-        use std::iter::FromIterator;
-        let splits = word
-            .char_indices()
-            .map(|(i, _)| (&word[..i], &word[i..]))
-            .chain([(word, "")].iter().copied())
-            .collect::<Vec<(&str, &str)>>();
-        let deletes = Self::single_deletes(&splits);
-        let inserts = self.single_inserts(&splits);
-        let replaces = self.single_replaces(&splits);
-        let transposes = Self::adjacent_transposes(&splits);
-        HashSet::from_iter(
-            deletes
-            .into_iter()
-            .chain(inserts.into_iter())
-            .chain(replaces.into_iter())
-            .chain(transposes.into_iter())
-        )
+    #[test]
+    fn correction_posterior_weighs_prior_against_edits() {
+        let corpus = format!("cat {}", "crate ".repeat(200));
+        let checker = SpellChecker::new(&corpus, ALPHABET_EN);
+
+        let posterior = checker.correction_posterior("caat");
+
+        assert_eq!(checker.correction("caat"), "cat");
+        assert_eq!(posterior[0].0, "crate");
+        assert_eq!(posterior[1].0, "cat");
+        assert!((posterior[0].1 + posterior[1].1 - 1.0).abs() < 1e-9);
+        assert!(checker.correction_posterior("xyzzy").is_empty());
     }
 
-    fn single_deletes(splits: &[(&str, &str)]) -> Vec<String> {
-        splits
-        .iter()
-        .filter(|(_, right)| !right.is_empty())
-        .map(|(left, right)| {
-            format!("{}{}", left, drop_leading_chars(1, right))
-        })
-        .collect()
+    #[test]
+    fn correct_in_context_prefers_likely_followers() {
+        let corpus = format!("{} over there. over there", "their house. ".repeat(5));
+        let checker = SpellChecker::new(&corpus, ALPHABET_EN);
+
+        assert_eq!(checker.correction("ther"), "their");
+        assert_eq!(checker.correct_in_context("over", "ther"), "there");
+        assert_eq!(checker.correct_in_context("Over", "ther"), "there");
+        assert_eq!(checker.correct_in_context("house", "ther"), "their");
+        assert_eq!(checker.correct_in_context("xyzzy", "ther"), "their");
+        assert_eq!(checker.correct_in_context("over", "xyzzy"), "xyzzy");
     }
 
-    fn adjacent_transposes(splits: &[(&str, &str)]) -> Vec<String> {
-        splits
-        .iter()
-        .filter(|(_, right)| right.chars().count() > 1)
-        .map(|(left, right)| {
-            let r = drop_leading_chars(2, right);
-            let right_nth = |i| right.chars().nth(i).unwrap();
-            format!("{}{}{}{}", left, right_nth(1), right_nth(0), r)
-        })
-        .collect()      
+    #[test]
+    fn correction_to_set_targets_only_allowed_words() {
+        let checker = SpellChecker::new("red red red green blue", ALPHABET_EN);
+        let allowed = as_set(&["green", "blue", "grey"]);
+
+        assert_eq!(checker.correction("gred"), "red");
+        assert_eq!(checker.correction_to_set("gred", &allowed), Some("grey".to_owned()));
+        assert_eq!(checker.correction_to_set("gren", &allowed), Some("green".to_owned()));
+        assert_eq!(checker.correction_to_set("bleu", &allowed), Some("blue".to_owned()));
+        assert_eq!(checker.correction_to_set("purple", &allowed), None);
+        assert_eq!(checker.correction_to_set("gren", &HashSet::new()), None);
     }
 
-    fn single_replaces(&self, splits: &[(&str, &str)]) -> Vec<String> {
-        splits
-        .iter()
-        .filter(|(_, right)| !right.is_empty())
-        .flat_map(|(left, right)| {
-            self.alphabet.chars().map(move |c| {
-                format!("{}{}{}", left, c, drop_leading_chars(1, right))
-            })
-        })
-        .collect()
+    #[test]
+    fn check_real_words_flags_words_unlikely_in_context() {
+        let corpus = format!("{} me too.", "we went to the store. ".repeat(20));
+        let checker = SpellChecker::new(&corpus, ALPHABET_EN);
+
+        let flagged = checker.check_real_words("We went too the store");
+
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].word, "too");
+        assert_eq!((flagged[0].start, flagged[0].end), (8, 11));
+        assert_eq!(flagged[0].suggestions, ["to"]);
+        assert!(checker.check_text("We went too the store").is_empty());
+        assert!(checker.check_real_words("we went to the store").is_empty());
+        assert!(checker.check_real_words("me too").is_empty());
+        assert!(checker.check_real_words("too").is_empty());
+        assert!(checker.check_real_words_with_ratio("went too the", 1e6).is_empty());
+        assert!(checker.check_real_words_with_ratio("me too", 0.0).is_empty());
     }
 
-    fn single_inserts(&self, splits: &[(&str, &str)]) -> Vec<String> {
-        splits
-        .iter()
-        .flat_map(|(left, right)| {
-            self.alphabet.chars().map(move |c| {
-                format!("{}{}{}", left, c, right)
-            })
-        })
-        .collect()
+    #[test]
+    fn decompose_compounds_into_known_parts() {
+        let checker = SpellChecker::new("sun flower flow sunflowers rain bow er", ALPHABET_EN);
+
+        assert_eq!(checker.decompose("sunflower"), Some(vec!["sun".to_owned(), "flower".to_owned()]));
+        assert_eq!(checker.decompose("Rainbow"), Some(vec!["rain".to_owned(), "bow".to_owned()]));
+        assert_eq!(checker.decompose("sunxflower"), None);
+        assert_eq!(checker.decompose(""), None);
     }
-    
-    pub fn edits2(&self, word: &str) -> HashSet<String> {
-        self.edits1(word)
-        .into_iter()
-        .flat_map(|e1| self.edits1(&e1))
-        .collect()
+
+    #[test]
+    fn edits1_recall() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        let pairs = [("ide", "ice"), ("mcie", "mice"), ("dce", "dice"), ("ile", "isle"), ("spy", "spie")];
+
+        assert!((checker.edits1_recall(&pairs) - 0.8).abs() < 1e-9);
+        assert_eq!(checker.edits1_recall(&[("nise", "nice")]), 0.0);
+        assert_eq!(checker.edits1_recall(&[]), 0.0);
     }
-}
 
-fn drop_leading_chars(n: usize, s: &str) -> &str {
-    s
-    .char_indices()
-    .skip(n)
-    .next()
-    .map(|(i, _)| &s[i..])
-    .unwrap_or("")
-}
+    #[test]
+    fn user_dict_words_are_accepted_and_preferred() {
+        let checker = SpellChecker::new("cat cat cat dog", ALPHABET_EN);
+        assert!(!checker.is_correct("cot"));
+        assert_eq!(checker.correction("cxt"), "cat");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let checker = checker.with_user_dict(WordCounter::from_str("cot"), 1.0);
+
+        assert!(checker.is_correct("Cot"));
+        assert_eq!(checker.correction("cxt"), "cot");
+        assert_eq!(checker.ranked_candidates("cxt")[0].0, "cot");
+        assert_eq!(checker.correction("cat"), "cat");
+    }
 
     #[test]
-    fn edits1_with_empty_alphabet() {
-        let checker = SpellChecker::new("", "");
-        let word = "ab";
-        let expected_words = as_set(&["a", "b", "ba"]);
-        
-        assert_eq!(checker.edits1(word), expected_words);
+    fn correction_preserving_case() {
+        let checker = SpellChecker::new("the cat sat a", ALPHABET_EN);
+
+        assert_eq!(checker.correction_preserving_case("THE"), "THE");
+        assert_eq!(checker.correction_preserving_case("Teh"), "The");
+        assert_eq!(checker.correction_preserving_case("CT"), "CAT");
+        assert_eq!(checker.correction_preserving_case("A"), "A");
+        assert_eq!(checker.correction_preserving_case("sat"), "sat");
+
+        let checker = SpellChecker::new("къща", ALPHABET_BG);
+        assert_eq!(checker.correction_preserving_case("КЪЩИ"), "КЪЩА");
     }
-    
+
     #[test]
-    fn edits1_with_nonempty_en_alphabet() {
-        let checker = SpellChecker::new("", "c");
-        let word = "ab";
-        let expected_words = as_set(&["cb", "b", "acb", "abc", "ba", "a", "cab", "ac"]);
+    fn scan_candidates() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
 
-        assert_eq!(checker.edits1(word), expected_words); 
+        assert_eq!(checker.scan_candidates("ide"), checker.candidates("ide"));
+        assert_eq!(checker.scan_candidates("mce"), ["ice", "mice"]);
+        assert_eq!(checker.scan_candidates("spiee"), ["spie"]);
+        assert_eq!(checker.scan_candidates("xyzzy"), ["xyzzy"]);
     }
-    
+
     #[test]
-    fn edits1_with_nonempty_bg_alphabet() {
-        let checker = SpellChecker::new("", "з");
-        let word = "ей";
-        let expected_words = as_set(&["ез", "езй", "й", "зй", "ейз", "зей", "е", "йе"]);
+    fn suggestions_are_ranked_with_probabilities() {
+        let checker = SpellChecker::new("bat cat cat eat fat fat fat hat", ALPHABET_EN);
+        let p = |word| checker.probability(word);
 
-        assert_eq!(checker.edits1(word), expected_words); 
+        assert_eq!(checker.suggestions("xat", 3), [("fat".to_owned(), p("fat")), ("cat".to_owned(), p("cat")), ("bat".to_owned(), p("bat"))]);
+        assert_eq!(checker.suggestions("xat", 10).len(), 5);
+        assert_eq!(checker.suggestions("cat", 5), [("cat".to_owned(), p("cat"))]);
+        assert_eq!(checker.suggestions("xyzzy", 5), [("xyzzy".to_owned(), 0.0)]);
     }
 
     #[test]
-    fn edits2_with_empty_alphabet() {
-        let checker = SpellChecker::new("", "");
-        let word = "ab";
-        let expected_words = as_set(&["", "ab", "a", "b"]);
+    fn try_correction_distinguishes_unknown_words() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
 
-        assert_eq!(checker.edits2(word), expected_words);
+        assert_eq!(checker.try_correction("ide"), Some("ice".to_owned()));
+        assert_eq!(checker.try_correction("mice"), Some("mice".to_owned()));
+        assert_eq!(checker.try_correction("xyzzy"), None);
+        assert_eq!(checker.correction("xyzzy"), "xyzzy");
     }
 
     #[test]
-    fn edits2_with_nonempty_en_alphabet() {
-        let checker = SpellChecker::new("", "c");
-        let word = "ab";
-        let expected_words = as_set(&[
-            "", "a", "cb", "cac", "bc", "cba", "acbc", "cab", "ac",
-            "acc", "abcc", "ab", "c", "accb", "cbc", "ca", "cc", "cacb",
-            "ccb", "acb", "abc", "cabc", "bca", "ccab", "b", "bac",
-        ]);
-        
-        assert_eq!(checker.edits2(word), expected_words);
+    fn suggestions_even_if_correct_skips_the_word_itself() {
+        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+
+        let neighbors = checker.suggestions_even_if_correct("mice", 3)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect::<Vec<String>>();
+
+        assert_eq!(checker.suggestions("mice", 3).len(), 1);
+        assert_eq!(neighbors, ["dice", "ice", "mic"]);
+        assert!(checker.suggestions_even_if_correct("xyzzy", 3).is_empty());
     }
 
     #[test]
-    fn edits2_with_nonempty_bg_alphabet() {
-        let checker = SpellChecker::new("", "з");
-        let word = "ей";
-        let expected_words = as_set(&[
-            "", "зез", "езйз", "ейз", "з", "зей", "зз", "зйе", "ез",
-            "езй", "йез", "зейз", "ейзз", "еззй", "ззй", "зй", "зе",
-            "йзе", "зезй", "е", "ззей", "ей", "йз", "езз", "й", "зйз",
-        ]);
-        
-        assert_eq!(checker.edits2(word), expected_words);
+    fn learning_the_missing_vocabulary() {
+        let mut checker = SpellChecker::new("the cat sat on a mat", ALPHABET_EN);
+        let text = "The cat sat on a futon.\nA quokka sat on the futon.";
+        assert!(checker.misspelling_rate(text) > 0.0);
+
+        let missing = checker.missing_vocabulary(text);
+        for word in &missing {
+            checker.learn(word);
+        }
+
+        assert_eq!(missing, ["futon", "quokka"]);
+        assert_eq!(checker.misspelling_rate(text), 0.0);
+        assert!(checker.missing_vocabulary("cta").is_empty());
     }
 
     #[test]
-    fn known_words_with_empty_corpus() {
-        let checker = SpellChecker::new("", ALPHABET_EN);
-        let words = as_set(&["a", "b"]);
+    fn transpositions_are_single_edits() {
+        let checker = SpellChecker::new("the ten", ALPHABET_EN);
 
-        let known_words = checker.known(&words);
-        
-        assert!(known_words.is_empty());
+        assert_eq!(checker.words_within("teh", 1), [("ten".to_owned(), 1), ("the".to_owned(), 1)]);
+        assert_eq!(crate::distance::damerau_levenshtein("teh", "the"), 1);
     }
 
     #[test]
-    fn known_words_with_nonempty_corpus_and_words_which_are_not_in_the_corpus() {
-        let checker = SpellChecker::new("one two three изненада", ALPHABET_EN);
-        let words = as_set(&["a", "й"]);
+    fn right_to_left_words() {
+        let checker = SpellChecker::new("שלום עולם עולם", ALPHABET_HE);
+        let edits = checker.edits1("שלם");
 
-        let known_words = checker.known(&words);
-        
-        assert!(known_words.is_empty());
+        assert!(edits.contains("שלום"));
+        assert!(edits.contains("לם"));
+        assert_eq!(checker.correction("שלם"), "שלום");
+        assert_eq!(checker.correction("עלום"), "עולם");
+
+        let misspellings = checker.check_text("שלום עלום\n\u{200f}שלם");
+
+        assert_eq!(misspellings.len(), 2);
+        assert_eq!((misspellings[0].line, misspellings[0].column), (0, 5));
+        assert_eq!((misspellings[1].line, misspellings[1].column), (1, 1));
+        assert_eq!(misspellings[1].word, "שלם");
     }
-    
+
     #[test]
-    fn known_words_with_nonempty_corpus_and_words_which_are_in_the_corpus() {
-        let checker = SpellChecker::new("one two three изненада", ALPHABET_EN);
-        let words = as_set(&["a", "b", "изненада"]);
-        let expected_word = "изненада".to_owned();
+    fn adjacent_key_replaces_are_preferred() {
+        let checker = SpellChecker::new("cut cut cut cat cat", ALPHABET_EN);
+        assert_eq!(checker.correction("cst"), "cut");
 
-        let known_words = checker.known(&words);
-        
-        assert_eq!(known_words.len(), 1);
-        assert!(known_words.contains(&&expected_word));
+        let checker = checker.with_keyboard_layout(KeyboardLayout::qwerty());
+
+        assert_eq!(checker.correction("cst"), "cat");
+        assert_eq!(checker.correction("cxt"), "cut");
     }
 
-    fn as_set(words: &[&str]) -> HashSet<String> {
-        words.iter().map(|&s| s.to_owned()).collect()
+    #[test]
+    fn neighborhood_density() {
+        let checker = SpellChecker::new("bat cat eat fat hat ca cast", ALPHABET_EN);
+
+        assert_eq!(checker.neighborhood_density("cat"), 6);
+        assert_eq!(checker.neighborhood_density("xat"), 5);
+        assert_eq!(checker.neighborhood_density("xyzzy"), 0);
     }
 
     #[test]
-    fn candidates_with_very_different_word_from_ones_on_corpus() {
+    fn correction_conservative_makes_single_edits_only() {
         let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
-        let word = "hamlet".to_owned();
 
-        let candidates = checker.candidates(&word);
+        assert_eq!(checker.correction("idd"), "ice");
+        assert_eq!(checker.correction_conservative("idd"), "idd");
+        assert_eq!(checker.correction_conservative("ide"), "ice");
+        assert_eq!(checker.correction_conservative("mice"), "mice");
+        assert!(!checker.is_correct("idd"));
+    }
 
-        assert_eq!(candidates.len(), 1);
-        assert!(candidates.contains(&word));
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn grapheme_mode_corrects_combined_letters() {
+        let mut corpus = WordCounter::from_str("cafe cafe");
+        corpus.add("cafe\u{301}");
+        let mut checker = SpellChecker::from_corpus(corpus, ALPHABET_EN);
+        assert_eq!(checker.correction("cae\u{301}f"), "cafe");
+
+        checker.edits = checker.edits.with_graphemes(true);
+
+        assert_eq!(checker.correction("cae\u{301}f"), "cafe\u{301}");
     }
-    
+
     #[test]
-    fn candidates_with_one_letter_difference() {
-        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
-        let word = "ide".to_owned();
-        let expected = "ice".to_owned();
+    fn smoothing_gives_unseen_words_a_probability() {
+        let checker = SpellChecker::new("a a b", ALPHABET_EN);
+        assert_eq!(checker.probability("c"), 0.0);
 
-        let candidates = checker.candidates(&word);
+        let checker = checker.with_smoothing(true);
 
-        assert_eq!(candidates.len(), 1);
-        assert!(candidates.contains(&expected));
+        assert!((checker.probability("c") - 1.0 / 5.0).abs() < 1e-9);
+        assert!((checker.probability("a") - 3.0 / 5.0).abs() < 1e-9);
+        assert!(checker.probability("b") > checker.probability("c"));
+        assert_eq!(checker.correction("a"), "a");
     }
-    
+
     #[test]
-    fn candidates_with_two_letters_difference() {
-        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
-        let word = "idde".to_owned();
-        let expected = ["dice", "ice", "isle"];
+    fn added_words_are_corrected_to() {
+        let mut checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
+        assert_eq!(checker.correction("rustacen"), "rustacen");
 
-        let candidates = checker.candidates(&word);
+        checker.add_word(" Rustacean, ");
+        checker.add_words(vec!["Ferris".to_owned(), "crab!".to_owned()]);
 
-        assert_eq!(candidates, expected);
+        assert_eq!(checker.correction("rustacen"), "rustacean");
+        assert!(checker.is_correct("ferris"));
+        assert_eq!(checker.frequency("crab").0, 1);
     }
 
     #[test]
-    fn correction_with_very_different_word_from_ones_on_corpus() {
+    fn bloom_filter_keeps_is_correct_exact() {
+        let corpus = "ice isle spie crie dice mice mic";
+        let mut checker = SpellChecker::new(corpus, ALPHABET_EN).with_bloom_filter(0.01);
+        checker.learn("rustacean");
+
+        for word in corpus.split_whitespace().chain(vec!["Rustacean"]) {
+            assert!(checker.is_correct(word));
+        }
+        for word in ["ide", "mce", "xyzzy"].iter() {
+            assert!(!checker.is_correct(word));
+        }
+    }
+
+    #[test]
+    fn candidates_progressive_stops_at_the_best_distance() {
         let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
-        let word = "hamlet".to_owned();
 
-        let correction = checker.correction(&word);
+        for word in ["mice", "ide", "idde", "mcie", "ise", "xyzzy", "spiie"].iter() {
+            let progressive = checker.candidates_progressive(word);
+            let candidates = checker.candidates(word);
 
-        assert_eq!(correction, word);
+            assert!(!progressive.is_empty());
+            assert!(progressive.iter().all(|candidate| candidates.contains(candidate)));
+        }
+        assert_eq!(checker.candidates_progressive("idde"), checker.candidates("idde"));
+        assert_eq!(checker.candidates_progressive("dicee"), ["dice"]);
+        assert_eq!(checker.candidates_progressive("mce"), ["ice"]);
+        assert_eq!(checker.candidates("mce"), ["ice", "mice"]);
     }
 
     #[test]
-    fn correction_with_one_letter_difference() {
-        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
-        let word = "ide".to_owned();
-        let expected = "ice".to_owned();
+    fn frequency_percentile_of_the_primary_corpus() {
+        let checker = SpellChecker::new("the the the cat sat", ALPHABET_EN);
 
-        let correction = checker.correction(&word);
+        assert_eq!(checker.frequency_percentile("The"), Some(1.0));
+        assert_eq!(checker.frequency_percentile("dog"), None);
+    }
 
-        assert_eq!(correction, expected);
+    #[test]
+    fn correct_text_keeps_the_layout_and_casing() {
+        let checker = SpellChecker::new("the cat sat don't well-known on mat", ALPHABET_EN);
+
+        assert_eq!(checker.correct_text("Teh cat, sat."), "The cat, sat.");
+        assert_eq!(checker.correct_text("CTA  sat\n\n on teh wel-known mat!"), "CAT  sat\n\n on the well-known mat!");
+        assert_eq!(checker.correct_text("don't  \t cta"), "don't  \t cat");
     }
 
     #[test]
-    fn correction_with_two_letters_difference() {
-        let checker = SpellChecker::new("ice isle spie crie dice mice mic", ALPHABET_EN);
-        let word = "idde";
-        let expected = "isle";
+    fn bk_candidates_match_candidates() {
+        let corpus = "the cat sat on a mat and ate the rat then spelling mistakes were corrected quickly";
+        let mut checker = SpellChecker::new(corpus, ALPHABET_EN);
+        let words = ["teh", "cta", "mat", "spelilng", "misteaks", "corected", "quikcly", "zzzz", "ca", "thn"];
 
-        let correction = checker.correction(&word);
+        for word in words.iter() {
+            assert_eq!(checker.bk_candidates(word), checker.candidates(word), "{}", word);
+            assert_eq!(checker.correction_bk(word), checker.correction(word), "{}", word);
+        }
 
-        assert_eq!(correction, expected);
+        checker.learn("zzz");
+        assert_eq!(checker.bk_candidates("zzzz"), ["zzz"]);
+    }
+
+    #[test]
+    fn alternative_correction_of_known_words() {
+        let checker = SpellChecker::new("their there there the cat cap", ALPHABET_EN);
+
+        assert_eq!(checker.correction("their"), "their");
+        assert_eq!(checker.alternative_correction("their").as_deref(), Some("there"));
+        assert_eq!(checker.alternative_correction("cat").as_deref(), Some("cap"));
+        assert_eq!(checker.alternative_correction("cta").as_deref(), Some("cat"));
+        assert_eq!(checker.alternative_correction("hamlet"), None);
+    }
+
+    #[test]
+    fn check_text_prefers_the_words_of_the_document() {
+        let checker = SpellChecker::new("the a of was is and cart cart cart carp", ALPHABET_EN);
+        let text = "The carp is a fish. A carp was caught and the carq was big.";
+
+        let misspellings = checker.check_text(text);
+
+        assert_eq!(checker.suggestions_for("carq"), ["cart", "carp"]);
+        assert_eq!(misspellings.len(), 4);
+        assert_eq!(misspellings[0].word, "fish");
+        assert_eq!(misspellings[2].word, "carq");
+        assert_eq!(misspellings[2].suggestions, ["carp", "cart"]);
+    }
+
+    #[test]
+    fn symspell_index_finds_the_same_corrections() {
+        let corpora = [
+            ("the cat sat on a mat and ate the rat then spelling mistakes were corrected quickly", ALPHABET_EN),
+            ("ice dice ice mice rice", ALPHABET_EN),
+            ("къща куче котка къщи", ALPHABET_BG),
+        ];
+        let words = ["teh", "cta", "mat", "spelilng", "misteaks", "quikcly", "zzzz", "ic", "mce", "кщъа", "кучи", "котак"];
+
+        for &(corpus, alphabet) in corpora.iter() {
+            let naive = SpellChecker::new(corpus, alphabet);
+            let mut indexed = SpellChecker::new(corpus, alphabet).with_symspell_index();
+            for word in words.iter() {
+                assert_eq!(indexed.candidates(word), naive.candidates(word), "{}", word);
+                assert_eq!(indexed.correction(word), naive.correction(word), "{}", word);
+            }
+
+            indexed.learn("zzz");
+            assert_eq!(indexed.correction("zzzz"), "zzz");
+        }
+    }
+
+    #[test]
+    fn completions_of_a_prefix() {
+        let mut checker = SpellChecker::new("car card care cart cat care cat care", ALPHABET_EN);
+
+        assert_eq!(checker.completions("car", 3), ["care", "car", "card"]);
+        assert_eq!(checker.completions("Car", 10), ["care", "car", "card", "cart"]);
+        assert_eq!(checker.completions("", 2), ["care", "cat"]);
+        assert!(checker.completions("dog", 3).is_empty());
+
+        checker.learn("cart");
+        checker.learn("cart");
+        assert_eq!(checker.completions("car", 2), ["care", "cart"]);
+        assert_eq!(SpellChecker::new("къща къщи къщи", ALPHABET_BG).completions("къщ", 5), ["къщи", "къща"]);
+    }
+
+    #[test]
+    fn merge_an_english_and_a_bulgarian_checker() {
+        let mut checker = SpellChecker::new("the cat sat on the mat", ALPHABET_EN);
+        let other = SpellChecker::new("къща куче котка", ALPHABET_BG);
+
+        checker.merge(&other).unwrap();
+
+        assert_eq!(checker.correction("cta"), "cat");
+        assert_eq!(checker.correction("кщъа"), "къща");
+        assert_eq!(checker.correction("котак"), "котка");
+        assert!(checker.is_correct("the") && checker.is_correct("куче"));
+        assert_eq!(checker.alphabet.chars().count(), 56);
+        assert_eq!(other.correction("cta"), "cta");
+    }
+
+    #[test]
+    fn merge_with_conflicting_options() {
+        let mut checker = SpellChecker::new("the cat", ALPHABET_EN);
+        let other = SpellChecker::new("къща", ALPHABET_BG).with_smoothing(true);
+
+        assert_eq!(checker.merge(&other), Err(ConfigError::ConflictingOptions));
+        assert!(!checker.is_correct("къща"));
+
+        let other = SpellChecker::new("къща", ALPHABET_BG).with_accent_insensitive(true).with_symspell_index();
+        checker.merge(&other).unwrap();
+        assert_eq!(checker.correction("кща"), "къща");
+        assert!(checker.is_correct("cat"));
+    }
+
+    #[test]
+    fn correction_margin_of_ambiguous_and_unambiguous_words() {
+        let checker = SpellChecker::new("cat cat cat mat mat sat ice", ALPHABET_EN);
+
+        assert!((checker.correction_margin("xat") - 1.0 / 7.0).abs() < 1e-9);
+        assert!((checker.correction_margin("ic") - 1.0 / 7.0).abs() < 1e-9);
+        assert!((checker.correction_margin("cta") - 3.0 / 7.0).abs() < 1e-9);
+        assert!(checker.correction_margin("cta") > checker.correction_margin("xat"));
+        assert_eq!(checker.correction_margin("hamlet"), 0.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn from_corpus_auto_derives_the_alphabet() {
+        let checker = SpellChecker::from_corpus_auto("Cat, коте 42 well-known!");
+
+        assert_eq!(checker.alphabet, "aceklnotwекот");
+        assert!(checker.alphabet.contains('c') && checker.alphabet.contains('к'));
+        assert_eq!(checker.correction("cta"), "cat");
+        assert_eq!(checker.correction("коет"), "коте");
+    }
+
+    #[test]
+    fn multilingual_checker_corrects_in_each_script() {
+        let corpus = "the cat sat on the mat къща куче котка";
+        let checker = SpellChecker::new_multilingual(corpus, &[ALPHABET_EN, ALPHABET_BG, "abc"]);
+        let english = SpellChecker::new(corpus, ALPHABET_EN);
+        let bulgarian = SpellChecker::new(corpus, ALPHABET_BG);
+
+        assert_eq!(checker.alphabet.chars().count(), 56);
+        assert_eq!(checker.edits1("ab").len(), english.edits1("ab").len() + 3 * 30 + 2 * 30);
+        for word in ["cta", "teh", "mta", "xat"].iter() {
+            assert_eq!(checker.correction(word), english.correction(word));
+            assert_eq!(checker.candidates(word), english.candidates(word));
+        }
+        for word in ["кщъа", "кучи", "котак"].iter() {
+            assert_eq!(checker.correction(word), bulgarian.correction(word));
+        }
+    }
+
+    #[test]
+    fn correct_text_to_matches_correct_text() {
+        let checker = SpellChecker::new("the cat sat don't well-known on mat къща", ALPHABET_EN);
+        let text = "Teh cat, sat.\r\n\n  CTA sat on teh wel-known mat!\nкъща, кщъа\n\ndon't  \t cta";
+        let mut out = Vec::new();
+
+        checker.correct_text_to(text, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), checker.correct_text(text));
+        checker.correct_text_to("", io::sink()).unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+// A hash of `word` which is the same on every platform and toolchain,
+// unlike `DefaultHasher`, so structures built on it can be persisted.
+// It is FNV-1a over the seed and the bytes of the word, followed by
+// the SplitMix64 finalizer to spread the bits of short words.
+pub(crate) fn stable_hash(seed: u64, word: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = OFFSET_BASIS;
+    for byte in seed.to_le_bytes().iter().chain(word.as_bytes()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hash_depends_on_the_seed_and_the_word() {
+        assert_eq!(stable_hash(0, "word"), 0x2e6d_1b18_050a_9dee);
+        assert_ne!(stable_hash(0, "word"), stable_hash(1, "word"));
+        assert_ne!(stable_hash(0, "word"), stable_hash(0, "ward"));
+    }
+}
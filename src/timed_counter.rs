@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::corpus::Corpus;
+
+// Word counts which decay exponentially with the given half-life.
+// Every word keeps a single decayed weight, updated on each add,
+// which is equivalent to summing the decayed weights of every add.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedCounter {
+    half_life: f64,
+    words_map: HashMap<String, Decayed>,
+    total: Decayed,
+    counts: HashMap<String, u32>,
+}
+
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Decayed {
+    weight: f64,
+    time: f64,
+}
+
+impl Decayed {
+    fn add(&mut self, time: f64, half_life: f64) {
+        if time >= self.time {
+            self.weight = self.weight * decay(time - self.time, half_life) + 1.0;
+            self.time = time;
+        }
+        else {
+            self.weight += decay(self.time - time, half_life);
+        }
+    }
+
+    fn at(&self, time: f64, half_life: f64) -> f64 {
+        self.weight * decay((time - self.time).max(0.0), half_life)
+    }
+}
+
+fn decay(elapsed: f64, half_life: f64) -> f64 {
+    0.5f64.powf(elapsed / half_life)
+}
+
+fn seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
+impl TimedCounter {
+    pub fn new(half_life: Duration) -> Self {
+        assert!(half_life > Duration::from_secs(0), "the half-life must be positive");
+        TimedCounter {
+            half_life: half_life.as_secs_f64(),
+            words_map: HashMap::new(),
+            total: Decayed::default(),
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: &str) {
+        self.add_at(item, SystemTime::now());
+    }
+
+    pub fn add_at(&mut self, item: &str, time: SystemTime) {
+        let word = item.trim().to_lowercase();
+        let time = seconds(time);
+        self.words_map.entry(word.clone()).or_default().add(time, self.half_life);
+        self.total.add(time, self.half_life);
+        *self.counts.entry(word).or_insert(0) += 1;
+    }
+
+    pub fn get(&self, word: &str) -> u32 {
+        *self.counts.get(word).unwrap_or(&0)
+    }
+
+    pub fn total_count(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    // The decayed weight of `word` as of the latest add.
+    pub fn weight(&self, word: &str) -> f64 {
+        self.words_map
+        .get(word)
+        .map(|w| w.at(self.total.time, self.half_life))
+        .unwrap_or(0.0)
+    }
+}
+
+impl Corpus for TimedCounter {
+    fn get(&self, word: &str) -> u32 {
+        TimedCounter::get(self, word)
+    }
+
+    fn total_count(&self) -> u32 {
+        TimedCounter::total_count(self)
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        self.counts.len()
+    }
+
+    fn probability(&self, word: &str) -> f64 {
+        if self.total.weight > 0.0 {
+            self.weight(word) / self.total.weight
+        }
+        else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spell_checker::{SpellChecker, ALPHABET_EN};
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn weights_halve_every_half_life() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut counter = TimedCounter::new(HOUR);
+
+        counter.add_at("old", start);
+        counter.add_at("new", start + 2 * HOUR);
+
+        assert!((counter.weight("old") - 0.25).abs() < 1e-9);
+        assert!((counter.weight("new") - 1.0).abs() < 1e-9);
+        assert!((counter.probability("old") - 0.2).abs() < 1e-9);
+        assert_eq!(counter.get("old"), 1);
+        assert_eq!(counter.total_count(), 2);
+    }
+
+    #[test]
+    fn adds_out_of_order() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut counter = TimedCounter::new(HOUR);
+
+        counter.add_at("new", start + HOUR);
+        counter.add_at("old", start);
+
+        assert!((counter.weight("old") - 0.5).abs() < 1e-9);
+        assert!((counter.probability("new") - 1.0 / 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recent_words_outrank_older_ones() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut counter = TimedCounter::new(HOUR);
+        for i in 0..3 {
+            counter.add_at("cat", start + i * HOUR);
+            counter.add_at("cot", start + 24 * HOUR + i * HOUR);
+        }
+
+        let checker = SpellChecker::from_corpus(counter, ALPHABET_EN);
+
+        assert_eq!(checker.correction("cxt"), "cot");
+    }
+}
@@ -0,0 +1,124 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub start: usize,
+    pub line: usize,
+    // In chars, i.e. Unicode scalar values, in logical order.
+    pub column: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+// A word is a run of alphabetic characters which may contain
+// hyphens and apostrophes, as long as they are surrounded by letters.
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let chars = text.char_indices().collect::<Vec<(usize, char)>>();
+    let is_word_char = |i: usize| {
+        let (_, c) = chars[i];
+        c.is_alphabetic() ||
+        (is_joiner(c) &&
+         i > 0 && chars[i - 1].1.is_alphabetic() &&
+         i + 1 < chars.len() && chars[i + 1].1.is_alphabetic())
+    };
+
+    let mut tokens = Vec::new();
+    let mut line = 0;
+    let mut column = 0;
+    let mut current: Option<(usize, usize, usize)> = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if is_word_char(i) {
+            if current.is_none() {
+                current = Some((offset, line, column));
+            }
+        }
+        else if let Some((start, l, col)) = current.take() {
+            tokens.push(Token { text: &text[start..offset], start, line: l, column: col });
+        }
+
+        if c == '\n' {
+            line += 1;
+            column = 0;
+        }
+        else {
+            column += 1;
+        }
+    }
+    if let Some((start, l, col)) = current {
+        tokens.push(Token { text: &text[start..], start, line: l, column: col });
+    }
+    tokens
+}
+
+// The words of each sentence, where a sentence ends
+// with a '.', '!' or '?' after its last word.
+pub fn tokenize_sentences(text: &str) -> Vec<Vec<Token<'_>>> {
+    let mut sentences = Vec::new();
+    let mut sentence = Vec::new();
+    let tokens = tokenize(text);
+    for (i, token) in tokens.iter().enumerate() {
+        sentence.push(*token);
+        let gap_end = tokens.get(i + 1).map_or(text.len(), |next| next.start);
+        if text[token.end()..gap_end].contains(is_sentence_end) {
+            sentences.push(std::mem::take(&mut sentence));
+        }
+    }
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+    sentences
+}
+
+fn is_sentence_end(c: char) -> bool {
+    c == '.' || c == '!' || c == '?'
+}
+
+fn is_joiner(c: char) -> bool {
+    c == '-' || c == '\''
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(tokens: &[Token<'a>]) -> Vec<&'a str> {
+        tokens.iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn tokenize_empty_text() {
+        assert!(tokenize("").is_empty());
+        assert!(tokenize(" ,. \n").is_empty());
+    }
+
+    #[test]
+    fn tokenize_keeps_inner_hyphens_and_apostrophes() {
+        let tokens = tokenize("don't -stop- the well-known 'song'");
+
+        assert_eq!(texts(&tokens), ["don't", "stop", "the", "well-known", "song"]);
+    }
+
+    #[test]
+    fn tokenize_reports_positions() {
+        let text = "ab, cd\n  ей";
+
+        let tokens = tokenize(text);
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!((tokens[1].start, tokens[1].line, tokens[1].column), (4, 0, 4));
+        assert_eq!((tokens[2].line, tokens[2].column), (1, 2));
+        assert_eq!(&text[tokens[2].start..tokens[2].end()], "ей");
+    }
+
+    #[test]
+    fn tokenize_sentences_splits_on_terminal_punctuation() {
+        let sentences = tokenize_sentences("Hi there. How are you?! Fine,\nthanks");
+
+        let texts = sentences.iter().map(|s| texts(s)).collect::<Vec<Vec<&str>>>();
+        assert_eq!(texts, [vec!["Hi", "there"], vec!["How", "are", "you"], vec!["Fine", "thanks"]]);
+        assert!(tokenize_sentences(" ... ").is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+use crate::corpus::Corpus;
+use crate::spell_checker::SpellChecker;
+
+// How many edits away the traced candidates may be.
+const TRACE_DISTANCE: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracedCandidate {
+    pub word: String,
+    pub count: u32,
+    pub probability: f64,
+}
+
+// Everything which went into the correction of a word, for bug reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrectionTrace {
+    pub input: String,
+    pub known: bool,
+    // The known words at each distance up to two which has any, nearest first.
+    pub buckets: Vec<(usize, Vec<TracedCandidate>)>,
+    // The candidates scored by `try_correction`, best first. Among equal
+    // scores the alphabetically last candidate wins, so it comes first.
+    pub scores: Vec<(String, f64)>,
+    pub correction: String,
+}
+
+impl CorrectionTrace {
+    // The runner-up the correction was compared to, if there was one.
+    pub fn runner_up(&self) -> Option<&(String, f64)> {
+        self.scores.get(1)
+    }
+}
+
+impl<C: Corpus> SpellChecker<C> {
+    pub fn trace(&self, word: &str) -> CorrectionTrace {
+        let mut buckets: Vec<(usize, Vec<TracedCandidate>)> = Vec::new();
+        for (candidate, distance) in self.words_within(word, TRACE_DISTANCE) {
+            let traced = TracedCandidate {
+                count: self.frequency(&candidate).0,
+                probability: self.probability(&candidate),
+                word: candidate,
+            };
+            match buckets.last_mut() {
+                Some((d, candidates)) if *d == distance => candidates.push(traced),
+                _ => buckets.push((distance, vec![traced])),
+            }
+        }
+        let mut scores = self.scored_candidates(word);
+        scores.sort_by(|(a, x), (b, y)| y.partial_cmp(x).unwrap().then_with(|| b.cmp(a)));
+
+        CorrectionTrace {
+            input: word.to_owned(),
+            known: self.is_correct(word),
+            buckets,
+            scores,
+            correction: self.correction(word),
+        }
+    }
+}
+
+impl fmt::Display for CorrectionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "input: {} ({})", self.input, if self.known { "known" } else { "unknown" })?;
+        for (distance, candidates) in &self.buckets {
+            let candidates = candidates
+                .iter()
+                .map(|c| format!("{} (count {}, p = {:.6})", c.word, c.count, c.probability))
+                .collect::<Vec<String>>();
+            writeln!(f, "distance {}: {}", distance, candidates.join(", "))?;
+        }
+        write!(f, "correction: {}", self.correction)?;
+        match (self.scores.first(), self.runner_up()) {
+            (Some((best, x)), Some((second, y))) => writeln!(f, ", {} ({:.6}) over {} ({:.6})", best, x, second, y),
+            (Some((best, x)), None) => writeln!(f, ", {} ({:.6}) was the only candidate", best, x),
+            _ => writeln!(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spell_checker::ALPHABET_EN;
+
+    #[test]
+    fn trace_of_an_ambiguous_word() {
+        let checker = SpellChecker::new("tea tea the the the ten to", ALPHABET_EN);
+
+        let trace = checker.trace("teh");
+
+        assert_eq!(trace.input, "teh");
+        assert!(!trace.known);
+        let distances = trace.buckets.iter().map(|(d, _)| *d).collect::<Vec<usize>>();
+        assert_eq!(distances, [1, 2]);
+        let nearest = &trace.buckets[0].1;
+        let words = nearest.iter().map(|c| c.word.as_str()).collect::<Vec<&str>>();
+        assert_eq!(words, ["tea", "ten", "the"]);
+        assert_eq!((nearest[0].count, nearest[0].probability), (2, 2.0 / 7.0));
+        assert_eq!(trace.buckets[1].1[0].word, "to");
+        assert_eq!(trace.correction, "the");
+        assert_eq!(trace.scores[0].0, "the");
+        assert_eq!(trace.runner_up().unwrap().0, "tea");
+        assert!(trace.to_string().ends_with("correction: the, the (0.428571) over tea (0.285714)\n"));
+    }
+
+    #[test]
+    fn trace_of_a_known_word() {
+        let checker = SpellChecker::new("tea the", ALPHABET_EN);
+
+        let trace = checker.trace("tea");
+
+        assert!(trace.known);
+        assert_eq!(trace.buckets[0].0, 0);
+        assert_eq!(trace.scores, [("tea".to_owned(), 0.5)]);
+        assert_eq!(trace.correction, "tea");
+    }
+}
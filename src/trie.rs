@@ -0,0 +1,131 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// Words with their counts, keyed on chars so any alphabet works.
+// Each node also keeps the highest count of a word below it,
+// which lets `completions` visit the most frequent words first.
+#[derive(Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Default, Clone)]
+struct Node {
+    children: HashMap<char, usize>,
+    count: u32,
+    best: u32,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Trie { nodes: vec![Node::default()] }
+    }
+
+    // Adds `count` occurrences of `word`.
+    pub fn insert(&mut self, word: &str, count: u32) {
+        let mut path = vec![0];
+        for c in word.chars() {
+            let current = *path.last().unwrap();
+            let next = match self.nodes[current].children.get(&c) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[current].children.insert(c, child);
+                    child
+                }
+            };
+            path.push(next);
+        }
+        let end = *path.last().unwrap();
+        self.nodes[end].count += count;
+        let total = self.nodes[end].count;
+        for node in path {
+            self.nodes[node].best = self.nodes[node].best.max(total);
+        }
+    }
+
+    pub fn count(&self, word: &str) -> u32 {
+        self.find(word).map_or(0, |node| self.nodes[node].count)
+    }
+
+    // Up to `n` of the words starting with `prefix`, the most frequent first.
+    // Ties are broken alphabetically.
+    pub fn completions(&self, prefix: &str, n: usize) -> Vec<(String, u32)> {
+        let mut result = Vec::new();
+        let start = match self.find(prefix) {
+            Some(node) => node,
+            None => return result,
+        };
+        // A node is expanded before any word below it, as it never has
+        // a lower count and its prefix never comes later alphabetically.
+        let mut heap = BinaryHeap::new();
+        heap.push((self.nodes[start].best, Reverse(prefix.to_owned()), Reverse(false), start));
+        while let Some((count, Reverse(key), Reverse(is_word), node)) = heap.pop() {
+            if result.len() == n {
+                break;
+            }
+            if is_word {
+                result.push((key, count));
+                continue;
+            }
+            let node = &self.nodes[node];
+            if node.count > 0 {
+                heap.push((node.count, Reverse(key.clone()), Reverse(true), 0));
+            }
+            for (&c, &child) in &node.children {
+                let mut child_key = key.clone();
+                child_key.push(c);
+                heap.push((self.nodes[child].best, Reverse(child_key), Reverse(false), child));
+            }
+        }
+        result
+    }
+
+    fn find(&self, prefix: &str) -> Option<usize> {
+        prefix
+        .chars()
+        .try_fold(0, |node, c| self.nodes[node].children.get(&c).copied())
+    }
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trie(words: &[(&str, u32)]) -> Trie {
+        let mut trie = Trie::new();
+        for &(word, count) in words {
+            trie.insert(word, count);
+        }
+        trie
+    }
+
+    #[test]
+    fn completions_by_frequency() {
+        let trie = trie(&[("car", 2), ("card", 1), ("care", 3), ("cart", 1), ("cat", 4)]);
+
+        let completions = trie.completions("car", 10);
+
+        assert_eq!(completions, [("care".to_owned(), 3), ("car".to_owned(), 2), ("card".to_owned(), 1), ("cart".to_owned(), 1)]);
+        assert_eq!(trie.completions("car", 1), [("care".to_owned(), 3)]);
+        assert_eq!(trie.completions("", 2), [("cat".to_owned(), 4), ("care".to_owned(), 3)]);
+        assert!(trie.completions("dog", 3).is_empty());
+        assert!(trie.completions("car", 0).is_empty());
+    }
+
+    #[test]
+    fn insert_adds_counts() {
+        let trie = trie(&[("къща", 1), ("къщи", 2), ("къща", 2)]);
+
+        assert_eq!(trie.count("къща"), 3);
+        assert_eq!(trie.count("къщ"), 0);
+        assert_eq!(trie.completions("къщ", 5), [("къща".to_owned(), 3), ("къщи".to_owned(), 2)]);
+    }
+}
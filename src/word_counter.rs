@@ -1,61 +1,249 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::io::{self, BufRead};
+use crate::quantized_counter::QuantizedCounter;
+use crate::tokenizer;
 
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WordCounter {
     words_map: HashMap<String, u32>,
+    // How often each word starts a sentence, empty unless tracked.
+    #[cfg_attr(feature = "serde", serde(default))]
+    initial_counts: HashMap<String, u32>,
 }
 
 impl WordCounter {
     pub fn new() -> Self {
         WordCounter {
             words_map: HashMap::new(),
+            initial_counts: HashMap::new(),
         }
     }
 
+    // Pre-sizes the map for `capacity` distinct words.
+    pub fn with_capacity(capacity: usize) -> Self {
+        WordCounter {
+            words_map: HashMap::with_capacity(capacity),
+            initial_counts: HashMap::new(),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &str) -> Self {
         let mut counter = Self::new();
-        for word in input.lines()
-            .map(|line| crate::clean_line(line))
-            .flat_map(|line| to_words(&line))
-        {
-            counter.add(&word);
+        counter.add_text(input);
+        counter
+    }
+
+    // Reads the corpus a line at a time, counting the words like `from_str`.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut counter = Self::new();
+        for line in reader.lines() {
+            counter.add_text(&line?);
+        }
+        Ok(counter)
+    }
+
+    pub(crate) fn add_text(&mut self, input: &str) {
+        for word in corpus_words(input) {
+            self.add(&word);
+        }
+    }
+
+    // Also tracks how often each word starts a sentence.
+    pub fn from_str_with_positions(input: &str) -> Self {
+        let mut counter = Self::new();
+        for sentence in tokenizer::tokenize_sentences(input) {
+            for (i, token) in sentence.iter().enumerate() {
+                counter.add(token.text);
+                if i == 0 {
+                    *counter.initial_counts.entry(token.text.to_lowercase()).or_insert(0) += 1;
+                }
+            }
         }
         counter
     }
 
+    // Keeps the casing of the words, e.g. "The" and "the" are counted apart.
+    pub fn from_str_case_sensitive(input: &str) -> Self {
+        let mut counter = Self::new();
+        for word in corpus_words(input) {
+            *counter.words_map.entry(word).or_insert(0) += 1;
+        }
+        counter
+    }
+
+    // Counts the matches of `pattern` as words, instead of the words
+    // left by `clean_line` between whitespace.
+    #[cfg(feature = "regex")]
+    pub fn from_str_regex(input: &str, pattern: &str) -> Result<Self, regex::Error> {
+        let regex = regex::Regex::new(pattern)?;
+        let mut counter = Self::new();
+        for word in regex.find_iter(input) {
+            counter.add(word.as_str());
+        }
+        Ok(counter)
+    }
+
+    // Counts the words of `lines` as they are read. After each batch of
+    // `batch_size` lines, and at the end, the map releases its excess
+    // capacity if it is more than twice its length, so the memory stays
+    // proportional to the vocabulary without rehashing on every batch.
+    // A batch size of 0 counts as 1.
+    pub fn train_batched<I>(&mut self, lines: I, batch_size: usize)
+    where
+        I: Iterator<Item = String>
+    {
+        let batch_size = batch_size.max(1);
+        for (i, line) in lines.enumerate() {
+            for word in corpus_words(&line) {
+                self.add(&word);
+            }
+            if (i + 1) % batch_size == 0 {
+                self.release_excess_capacity();
+            }
+        }
+        self.release_excess_capacity();
+    }
+
+    fn release_excess_capacity(&mut self) {
+        if self.words_map.capacity() > 2 * self.words_map.len() {
+            self.words_map.shrink_to_fit();
+        }
+    }
+
     pub fn add(&mut self, item: &str) {
+        self.add_count(item, 1);
+    }
+
+    pub(crate) fn add_count(&mut self, item: &str, n: u32) {
         let word = item.trim().to_lowercase();
         let count = self.words_map.entry(word).or_insert(0);
-        *count += 1;
+        *count += n;
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.words_map.capacity()
+    }
+
+    // Adds the counts of `other` to this counter.
+    pub fn merge(&mut self, other: &WordCounter) {
+        for (word, &count) in &other.words_map {
+            *self.words_map.entry(word.clone()).or_insert(0) += count;
+        }
+        for (word, &count) in &other.initial_counts {
+            *self.initial_counts.entry(word.clone()).or_insert(0) += count;
+        }
     }
 
     pub fn words(&self) -> Vec<&String> {
         let mut words = self.words_map.keys().collect::<Vec<&String>>();
-        words.sort_unstable_by(|&a, &b| a.cmp(b));
+        words.sort_unstable();
         words
     }
 
+    // The distinct words, sorted, one per line.
+    pub fn to_wordlist(&self) -> String {
+        self.words()
+        .iter()
+        .map(|word| word.as_str())
+        .collect::<Vec<&str>>()
+        .join("\n")
+    }
+
     pub fn get(&self, word: &str) -> u32 {
         *self.words_map.get(word).unwrap_or(&0)
     }
 
+    // The fraction of the occurrences of `word` at the start of a sentence,
+    // 0.0 for unknown words or when positions are not tracked.
+    pub fn initial_ratio(&self, word: &str) -> f64 {
+        match self.get(word) {
+            0 => 0.0,
+            count => *self.initial_counts.get(word).unwrap_or(&0) as f64 / count as f64,
+        }
+    }
+
+    // The fraction of the other distinct words which are rarer than `word`,
+    // so 0.0 is the rarest and 1.0 the most common. None for unknown words.
+    pub fn frequency_percentile(&self, word: &str) -> Option<f64> {
+        let count = *self.words_map.get(word)?;
+        if self.words_map.len() == 1 {
+            return Some(1.0);
+        }
+        let rarer = self.words_map.values().filter(|&&other| other < count).count();
+        Some(rarer as f64 / (self.words_map.len() - 1) as f64)
+    }
+
+    pub fn vocabulary_size(&self) -> usize {
+        self.words_map.len()
+    }
+
     pub fn total_count(&self) -> u32 {
         self.words_map.values().sum()
     }
+
+    pub fn frequency(&self, word: &str) -> (u32, u32) {
+        (self.get(word), self.total_count())
+    }
+
+    // The Shannon entropy of the word distribution, in bits.
+    pub fn entropy(&self) -> f64 {
+        let total = self.total_count() as f64;
+        self.words_map
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+    }
+
+    // The Kullback-Leibler divergence of this word distribution from the one
+    // of `other`, in bits. Both are add-one smoothed over the union of their
+    // words, i.e. P(word) = (count + 1) / (total count + union size), so
+    // words seen in only one counter don't make the divergence infinite.
+    pub fn kl_divergence(&self, other: &WordCounter) -> f64 {
+        let mut union = self.words_map.keys().collect::<Vec<&String>>();
+        union.extend(other.words_map.keys().filter(|word| !self.words_map.contains_key(*word)));
+        let own_total = (self.total_count() as usize + union.len()) as f64;
+        let other_total = (other.total_count() as usize + union.len()) as f64;
+        union
+        .iter()
+        .map(|word| {
+            let p = (self.get(word) + 1) as f64 / own_total;
+            let q = (other.get(word) + 1) as f64 / other_total;
+            p * (p / q).log2()
+        })
+        .sum()
+    }
+
+    // A compact approximation of this counter, for read-only models.
+    pub fn quantized(&self) -> QuantizedCounter {
+        QuantizedCounter::from_counts(&self.words_map)
+    }
 }
 
 impl std::fmt::Display for WordCounter {
     fn fmt(&self, f: &mut fmt::Formatter) -> std::fmt::Result {
-        let _ = writeln!(f, "WordCounter, total count: {}", self.total_count())?;
+        writeln!(f, "WordCounter, total count: {}", self.total_count())?;
         let mut pairs = self.words_map.iter().collect::<Vec<(&String, &u32)>>();
-        pairs.sort_unstable_by(|(_, x), (_, y)| y.cmp(&x));
+        pairs.sort_unstable_by(|(_, x), (_, y)| y.cmp(x));
         for (word, count) in &pairs {
-            let _ = writeln!(f, "{}: {}", word, count)?;
+            writeln!(f, "{}: {}", word, count)?;
         }
         Ok(())
     }
 }
 
+pub(crate) fn corpus_words(input: &str) -> impl Iterator<Item = String> + '_ {
+    input
+    .lines()
+    .map(crate::clean_line)
+    .flat_map(|line| to_words(&line))
+}
+
 fn to_words(line: &str) -> Vec<String> {
     line
     .split_whitespace()
@@ -101,4 +289,150 @@ mod tests {
 
         assert_eq!(counter.get("word"), 4);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn entropy() {
+        assert_eq!(WordCounter::new().entropy(), 0.0);
+        assert_eq!(WordCounter::from_str("a a a").entropy(), 0.0);
+        assert!((WordCounter::from_str("a b a b").entropy() - 1.0).abs() < 1e-9);
+        assert!((WordCounter::from_str("a a a b").entropy() - 0.811_278_124_459_132_8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn kl_divergence() {
+        let counter = WordCounter::from_str("a a a b");
+
+        assert_eq!(WordCounter::new().kl_divergence(&WordCounter::new()), 0.0);
+        assert!(counter.kl_divergence(&counter).abs() < 1e-9);
+        assert!((counter.kl_divergence(&WordCounter::from_str("a b")) - 0.081_704_165_945_510_4).abs() < 1e-9);
+        assert!((WordCounter::from_str("a b").kl_divergence(&WordCounter::from_str("a c")) - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frequency() {
+        let counter = WordCounter::from_str("a b b c");
+
+        assert_eq!(counter.frequency("b"), (counter.get("b"), counter.total_count()));
+        assert_eq!(counter.frequency("b"), (2, 4));
+        assert_eq!(counter.frequency("d"), (0, 4));
+    }
+
+    #[test]
+    fn case_sensitive_counter() {
+        let counter = WordCounter::from_str_case_sensitive("The the THE the");
+
+        assert_eq!(counter.words(), ["THE", "The", "the"]);
+        assert_eq!(counter.get("the"), 2);
+        assert_eq!(counter.get("The"), 1);
+    }
+
+    #[test]
+    fn batched_training_matches_from_str() {
+        let text = "first line\nSecond LiNe, again\n\n THIRD LINE\nfirst";
+        let expected = WordCounter::from_str(text);
+
+        for batch_size in [1, 2, 100].iter() {
+            let mut counter = WordCounter::new();
+            counter.train_batched(text.lines().map(String::from), *batch_size);
+
+            assert_eq!(counter.words(), expected.words());
+            for word in expected.words() {
+                assert_eq!(counter.get(word), expected.get(word));
+            }
+        }
+    }
+
+    #[test]
+    fn batched_training_releases_excess_capacity() {
+        let lines = (1..=100).map(|i| format!("{} common", "a".repeat(i)));
+        let mut counter = WordCounter::with_capacity(100_000);
+
+        counter.train_batched(lines, 0);
+
+        assert_eq!(counter.get("common"), 100);
+        assert_eq!(counter.vocabulary_size(), 101);
+        assert!(counter.capacity() <= 2 * counter.vocabulary_size());
+    }
+
+    #[test]
+    fn initial_ratio() {
+        let counter = WordCounter::from_str_with_positions("However, it rains. However it is warm. I like it, however.");
+
+        assert!((counter.initial_ratio("however") - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(counter.initial_ratio("it"), 0.0);
+        assert_eq!(counter.initial_ratio("i"), 1.0);
+        assert_eq!(counter.initial_ratio("unknown"), 0.0);
+        assert_eq!(counter.get("it"), 3);
+        assert_eq!(WordCounter::from_str("However, it rains.").initial_ratio("however"), 0.0);
+    }
+
+    #[test]
+    fn counter_with_capacity() {
+        let text = "first line\nSecond LiNe\n THIRD LINE\n";
+        let mut counter = WordCounter::with_capacity(1000);
+
+        counter.add_text(text);
+
+        assert!(counter.capacity() >= 1000);
+        assert_eq!(counter.words(), WordCounter::from_str(text).words());
+        assert_eq!(counter.get("line"), 3);
+    }
+
+    #[test]
+    fn counter_from_reader() {
+        let text = "first line\r\nSecond LiNe, again\n\n THIRD LINE\nfirst";
+
+        let counter = WordCounter::from_reader(io::Cursor::new(text)).unwrap();
+
+        let expected = WordCounter::from_str(text);
+        assert_eq!(counter.words(), expected.words());
+        assert_eq!(counter.get("line"), 3);
+        assert_eq!(counter.get("first"), 2);
+        assert_eq!(counter.total_count(), expected.total_count());
+    }
+
+    #[test]
+    fn to_wordlist() {
+        let counter = WordCounter::from_str("the cat\nThe mat, a cat");
+
+        assert_eq!(counter.to_wordlist(), "a\ncat\nmat\nthe");
+        assert_eq!(WordCounter::new().to_wordlist(), "");
+    }
+
+    #[test]
+    fn merge() {
+        let mut counter = WordCounter::from_str("a b b");
+        let other = WordCounter::from_str("b c");
+
+        counter.merge(&other);
+
+        assert_eq!(counter.get("b"), 3);
+        assert_eq!(counter.get("a"), 1);
+        assert_eq!(counter.get("c"), 1);
+        assert_eq!(counter.total_count(), 5);
+        assert_eq!(other.total_count(), 2);
+    }
+
+    #[test]
+    fn frequency_percentile() {
+        let counter = WordCounter::from_str("the the the the the a a of of rare");
+
+        assert_eq!(counter.frequency_percentile("the"), Some(1.0));
+        assert_eq!(counter.frequency_percentile("rare"), Some(0.0));
+        assert!((counter.frequency_percentile("of").unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(counter.frequency_percentile("unknown"), None);
+        assert_eq!(WordCounter::from_str("only").frequency_percentile("only"), Some(1.0));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn counter_from_regex_matches() {
+        let text = "\"Don't!\" -- she said; (rock'n'roll,again)...Äpfel/äpfel 42";
+
+        let counter = WordCounter::from_str_regex(text, r"[\p{L}']+").unwrap();
+
+        assert_eq!(counter.words(), ["again", "don't", "rock'n'roll", "said", "she", "äpfel"]);
+        assert_eq!(counter.get("äpfel"), 2);
+        assert!(WordCounter::from_str_regex(text, "[").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn corpus_file(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("spell_checker_cli_{}_{}.txt", name, std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_spell_checker"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn corrects_positional_words() {
+    let corpus = corpus_file("positional", "ice isle spie crie dice mice mic");
+
+    let output = run(&["--corpus", corpus.to_str().unwrap(), "ide", "mice"], "");
+    std::fs::remove_file(&corpus).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "ide\tice\nmice\tmice\n");
+}
+
+#[test]
+fn corrects_words_from_stdin() {
+    let corpus = corpus_file("stdin", "къща куче");
+
+    let output = run(&["--corpus", corpus.to_str().unwrap(), "--alphabet", "bg"], "къщи\nкуче кучр\n");
+    std::fs::remove_file(&corpus).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "къщи\tкъща\nкуче\tкуче\nкучр\tкуче\n");
+}
+
+#[test]
+fn fails_on_a_missing_corpus() {
+    let output = run(&["--corpus", "/nonexistent/corpus.txt", "word"], "");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("cannot read the corpus"));
+}
+
+#[test]
+fn interactive_mode_corrects_each_line() {
+    let corpus = corpus_file("interactive", "ice isle spie crie dice mice mic");
+
+    let output = run(&["--corpus", corpus.to_str().unwrap(), "--interactive"], "ide\n\n  \nmice\nxyzzy");
+    std::fs::remove_file(&corpus).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "ice\nmice\nxyzzy\n");
+}